@@ -1,79 +1,82 @@
 use chrono::{DateTime, Utc};
+use std::env;
 use std::io;
-use tinkoff_bank::Client;
+use tinkoff_bank::{AuthenticatedClient, Client, FileStore, LoginFlow, Prompt, SessionStore};
 use uuid::Uuid;
 
+const STORE_PATH: &str = "tinkoff-session.bin";
+const STORE_KEY_VAR: &str = "TINKOFF_STORE_KEY";
+
+struct StdinPrompt;
+
+impl Prompt for StdinPrompt {
+    fn phone(&mut self) -> String {
+        input("Enter phone number, like +79998887766: ")
+    }
+
+    fn sms_code(&mut self) -> String {
+        input("Enter the code from sms: ")
+    }
+
+    fn password(&mut self) -> String {
+        input("Enter your password: ")
+    }
+}
+
 #[tokio::main]
 async fn main() {
-    let device_id = Uuid::new_v4().to_string();
-    let client = Client::default();
-
-    print_section("Request session");
-    let session = dbg!(client.request_session(&device_id).await);
-    let session_id = session.payload.unwrap().id;
-    dbg!(client.ping(&device_id, &session_id).await);
-
-    print_section("Auth by phone");
-    let phone = input("Enter phone number, like +79998887766: ");
-    let confirmation_details = dbg!(client.auth_by_phone(&device_id, &session_id, &phone).await);
-    let operation_ticket = confirmation_details.operation_ticket.unwrap();
-
-    print_section("Confirm auth by phone");
-    let sms_code = input("Enter the code from sms: ");
-    dbg!(
-        client
-            .confirm_auth_by_phone(&device_id, &session_id, &operation_ticket, &sms_code)
-            .await
-    );
-    dbg!(client.ping(&device_id, &session_id).await);
-
-    print_section("Auth by password");
-    let password = input("Enter your password: ");
-    dbg!(
-        client
-            .auth_by_password(&device_id, &session_id, &password)
-            .await
-    );
-    dbg!(client.ping(&device_id, &session_id).await);
-
-    print_section("Set auth pin");
-    let auth_pin = Uuid::new_v4().to_string();
-    dbg!(
-        client
-            .set_auth_pin(&device_id, &session_id, &auth_pin)
-            .await
-    );
-
-    print_section("Auth by pin");
-    let new_session_id = dbg!(client.request_session(&device_id,).await)
-        .payload
-        .unwrap()
-        .id;
-    dbg!(
-        client
-            .auth_by_pin(&device_id, &new_session_id, &auth_pin, &session_id)
-            .await
-    );
-    dbg!(client.ping(&device_id, &new_session_id).await);
+    let store = FileStore::new(STORE_PATH, &store_key());
+
+    print_section("Resume session");
+    let mut client = match AuthenticatedClient::resume(Client::default(), &store).await {
+        Ok(Some(client)) => client,
+        _ => {
+            print_section("Login");
+            let device_id = Uuid::new_v4().to_string();
+            let pin_hash = Uuid::new_v4().to_string();
+            LoginFlow::new(Client::default(), &device_id)
+                .with_pin_hash(&pin_hash)
+                .run(&mut StdinPrompt)
+                .await
+                .unwrap()
+        }
+    };
+    store.save(&client.credentials()).unwrap();
+    dbg!(client.ping().await.unwrap());
 
     print_section("List accounts");
-    let accounts = dbg!(client.list_accounts(&device_id, &new_session_id).await)
-        .payload
-        .unwrap();
+    let accounts = dbg!(client.list_accounts().await.unwrap()).payload.unwrap();
     let account = &accounts[0];
 
     print_section("List operations");
-    dbg!(
-        client
-            .list_operations(
-                &device_id,
-                &new_session_id,
-                &account.id,
-                dt("2021-02-01T00:00:00Z"),
-                dt("2021-02-28T00:00:00Z")
-            )
-            .await
-    );
+    dbg!(client
+        .list_operations(
+            &account.id,
+            dt("2021-02-01T00:00:00Z"),
+            dt("2021-02-28T00:00:00Z")
+        )
+        .await
+        .unwrap());
+
+    // session could be renewed in the meantime
+    store.save(&client.credentials()).unwrap();
+}
+
+fn store_key() -> [u8; 32] {
+    if let Ok(value) = env::var(STORE_KEY_VAR) {
+        let mut key = [0; 32];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&value[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        return key;
+    }
+
+    let key = FileStore::generate_key();
+    let hex: String = key.iter().map(|byte| format!("{:02x}", byte)).collect();
+    println!("Generated new store key, to resume session next time run:");
+    println!("export {}={}", STORE_KEY_VAR, hex);
+
+    key
 }
 
 fn input(text: &str) -> String {
@@ -0,0 +1,311 @@
+use crate::client::Client;
+use crate::confirmation::{ConfirmationData, PendingConfirmation};
+use crate::data_structs::*;
+use crate::error::Error;
+use crate::operations::RangeOptions;
+use crate::store::{Credentials, SessionStore};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Client bound to device and session, so you don't need to pass them to every call.
+///
+/// Keeps access level of the session up to date with every ping. If pin hash is known, expired
+/// session is renewed by pin automatically and failed call is retried once with the new session.
+#[derive(Clone)]
+pub struct AuthenticatedClient {
+    client: Client,
+    device_id: String,
+    session_id: String,
+    access_level: AccessLevel,
+    user_id: Option<String>,
+    pin_hash: Option<String>,
+    expires_at: Option<Instant>,
+}
+
+impl AuthenticatedClient {
+    /// Binds `Client` to existing session.
+    ///
+    /// Pings API to find out access level of the session, so it fails if session is unknown.
+    /// Session lifetime is unknown in this case, so it will be renewed only after API reports
+    /// that session is expired (and only if pin hash is provided with `with_pin_hash`).
+    pub async fn new(client: Client, device_id: &str, session_id: &str) -> Result<Self, Error> {
+        let mut authenticated = AuthenticatedClient {
+            client,
+            device_id: device_id.to_owned(),
+            session_id: session_id.to_owned(),
+            access_level: AccessLevel::Anonymous,
+            user_id: None,
+            pin_hash: None,
+            expires_at: None,
+        };
+        authenticated.ping().await?;
+
+        Ok(authenticated)
+    }
+
+    pub(crate) fn from_parts(
+        client: Client,
+        device_id: String,
+        session_id: String,
+        user_info: UserInfo,
+        pin_hash: Option<String>,
+        expires_at: Option<Instant>,
+    ) -> Self {
+        let mut authenticated = AuthenticatedClient {
+            client,
+            device_id,
+            session_id,
+            access_level: AccessLevel::Anonymous,
+            user_id: None,
+            pin_hash,
+            expires_at,
+        };
+        authenticated.remember_user(user_info);
+
+        authenticated
+    }
+
+    /// Requests new session and authorizes it by pin hash, previously set with
+    /// `Client::set_auth_pin` for `old_session_id`.
+    ///
+    /// Pin hash is remembered to renew session in future.
+    pub async fn by_pin(
+        client: Client,
+        device_id: &str,
+        pin_hash: &str,
+        old_session_id: &str,
+    ) -> Result<Self, Error> {
+        let mut authenticated = AuthenticatedClient {
+            client,
+            device_id: device_id.to_owned(),
+            session_id: old_session_id.to_owned(),
+            access_level: AccessLevel::Anonymous,
+            user_id: None,
+            pin_hash: Some(pin_hash.to_owned()),
+            expires_at: None,
+        };
+        authenticated.renew().await?;
+
+        Ok(authenticated)
+    }
+
+    /// Resumes work with credentials from store by authorizing new session with saved pin hash.
+    ///
+    /// Returns `None` if store doesn't have session id and pin hash yet, so full login is needed.
+    /// Credentials with new session id are saved back to store.
+    pub async fn resume(client: Client, store: &dyn SessionStore) -> Result<Option<Self>, Error> {
+        let credentials = match store.load()? {
+            Some(credentials) => credentials,
+            None => return Ok(None),
+        };
+        let (session_id, pin_hash) = match (&credentials.session_id, &credentials.pin_hash) {
+            (Some(session_id), Some(pin_hash)) => (session_id, pin_hash),
+            _ => return Ok(None),
+        };
+
+        let mut authenticated =
+            Self::by_pin(client, &credentials.device_id, pin_hash, session_id).await?;
+        if authenticated.user_id.is_none() {
+            authenticated.user_id = credentials.user_id;
+        }
+        store.save(&authenticated.credentials())?;
+
+        Ok(Some(authenticated))
+    }
+
+    /// Remember pin hash to renew session automatically when it expires.
+    pub fn with_pin_hash(mut self, pin_hash: &str) -> Self {
+        self.pin_hash = Some(pin_hash.to_owned());
+        self
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Access level known after last ping or auth.
+    pub fn access_level(&self) -> &AccessLevel {
+        &self.access_level
+    }
+
+    /// User id known after last ping or auth.
+    pub fn user_id(&self) -> Option<&str> {
+        self.user_id.as_deref()
+    }
+
+    /// Snapshot of current credentials to save them with `SessionStore`.
+    ///
+    /// Session id changes after every renewal, so save credentials again after long work.
+    pub fn credentials(&self) -> Credentials {
+        Credentials {
+            device_id: self.device_id.clone(),
+            session_id: Some(self.session_id.clone()),
+            pin_hash: self.pin_hash.clone(),
+            user_id: self.user_id.clone(),
+        }
+    }
+
+    /// Whether session lifetime (`Session.ttl`) is over.
+    ///
+    /// Always `false` if lifetime is unknown.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Instant::now())
+    }
+
+    /// Replace current session with a new one authorized by pin hash.
+    ///
+    /// Does nothing if pin hash is unknown.
+    pub async fn renew(&mut self) -> Result<(), Error> {
+        let pin_hash = match &self.pin_hash {
+            Some(pin_hash) => pin_hash,
+            None => return Ok(()),
+        };
+
+        let session = self
+            .client
+            .request_session(&self.device_id)
+            .await?
+            .payload
+            .ok_or(Error::MissingPayload)?;
+        let user_info = self
+            .client
+            .auth_by_pin(&self.device_id, &session.id, pin_hash, &self.session_id)
+            .await?
+            .payload
+            .ok_or(Error::MissingPayload)?;
+
+        self.session_id = session.id;
+        self.remember_user(user_info);
+        self.expires_at = Some(Instant::now() + Duration::from_secs(session.ttl.into()));
+
+        Ok(())
+    }
+
+    /// Ping bank API for details about current session and refresh known access level.
+    pub async fn ping(&mut self) -> Result<ResponsePayload<UserInfo>, Error> {
+        let resp = self
+            .with_session(|client, device_id, session_id| async move {
+                client.ping(&device_id, &session_id).await
+            })
+            .await?;
+        if let Some(info) = &resp.payload {
+            self.remember_user(info.clone());
+        }
+
+        Ok(resp)
+    }
+
+    /// List all bank accounts as flat list.
+    pub async fn list_accounts(&mut self) -> Result<ResponsePayload<Vec<Account>>, Error> {
+        self.with_session(|client, device_id, session_id| async move {
+            client.list_accounts(&device_id, &session_id).await
+        })
+        .await
+    }
+
+    /// List operations for specified account id.
+    pub async fn list_operations(
+        &mut self,
+        account_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<ResponsePayload<Vec<Operation>>, Error> {
+        self.with_session(|client, device_id, session_id| async move {
+            client
+                .list_operations(&device_id, &session_id, account_id, start, end)
+                .await
+        })
+        .await
+    }
+
+    /// List operations for any long interval, see `Client::list_operations_range`.
+    pub async fn list_operations_range(
+        &mut self,
+        account_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        options: &RangeOptions,
+    ) -> Result<Vec<Operation>, Error> {
+        self.with_session(|client, device_id, session_id| async move {
+            client
+                .list_operations_range(&device_id, &session_id, account_id, start, end, options)
+                .await
+        })
+        .await
+    }
+
+    /// Call any API endpoint with the current session, see `Client::call`.
+    pub async fn call<T: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        query: &[(&str, &str)],
+        form: &[(&str, &str)],
+    ) -> Result<ResponsePayload<T>, Error> {
+        self.with_session(|client, device_id, session_id| async move {
+            client
+                .call(path, &device_id, Some(&session_id), query, form)
+                .await
+        })
+        .await
+    }
+
+    /// Confirm operation from `ResponsePayload::pending_confirmation`.
+    ///
+    /// Session isn't renewed here, because operation ticket belongs to the current session.
+    pub async fn confirm<T: DeserializeOwned>(
+        &self,
+        pending: &PendingConfirmation,
+        data: &ConfirmationData,
+    ) -> Result<ResponsePayload<T>, Error> {
+        self.client
+            .confirm_pending(&self.device_id, &self.session_id, pending, data)
+            .await
+    }
+
+    fn remember_user(&mut self, info: UserInfo) {
+        self.access_level = info.access_level;
+        if !info.user_id.is_empty() {
+            self.user_id = Some(info.user_id);
+        }
+    }
+
+    async fn with_session<T, F, Fut>(&mut self, call: F) -> Result<T, Error>
+    where
+        F: Fn(Client, String, String) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        if self.is_expired() {
+            self.renew().await?;
+        }
+
+        let result = call(
+            self.client.clone(),
+            self.device_id.clone(),
+            self.session_id.clone(),
+        )
+        .await;
+        match result {
+            Err(err) if err.is_session_expired() && self.pin_hash.is_some() => {
+                self.renew().await?;
+                call(
+                    self.client.clone(),
+                    self.device_id.clone(),
+                    self.session_id.clone(),
+                )
+                .await
+            }
+            other => other,
+        }
+    }
+}
@@ -1,23 +1,211 @@
+use crate::confirmation::{ConfirmationData, PendingConfirmation};
 use crate::data_structs::*;
+use crate::error::Error;
+use crate::operations::{self, RangeOptions};
+use crate::profile::DeviceProfile;
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
 use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt, TryStreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::time::Duration;
 
 const API_URL: &str = "https://api.tinkoff.ru";
-const DEFAULT_PARAMS: [(&str, &str); 6] = [
-    ("appVersion", "5.5.1"),
-    ("connectionSubtype", "4G"),
-    ("appName", "mobile"),
-    ("origin", "mobile,ib5,loyalty,platform"),
-    ("connectionType", "Cellular"),
-    ("platform", "android"),
-    // pass device id too
-];
+const CONFIRM_PATH: &str = "/v1/confirm";
+/// Longer `Retry-After` hints are most likely broken, so they are cut to this.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// Response as it came from API, before any checks and decoding.
+#[derive(Debug)]
+pub struct RawResponse {
+    pub status: reqwest::StatusCode,
+    pub headers: reqwest::header::HeaderMap,
+    pub body: String,
+}
+
+impl RawResponse {
+    /// Delay requested by API with `Retry-After` header, either in seconds or as HTTP date.
+    ///
+    /// Delay is capped at an hour.
+    pub fn retry_after(&self) -> Option<Duration> {
+        let value = self
+            .headers
+            .get(reqwest::header::RETRY_AFTER)?
+            .to_str()
+            .ok()?
+            .trim();
+        let delay = match value.parse::<u64>() {
+            Ok(seconds) => Duration::from_secs(seconds),
+            Err(_) => {
+                let date = DateTime::parse_from_rfc2822(value).ok()?;
+                (date.with_timezone(&Utc) - Utc::now()).to_std().ok()?
+            }
+        };
+
+        Some(delay.min(MAX_RETRY_AFTER))
+    }
+
+    /// Checks status and result code and decodes payload, like typed methods of `Client` do.
+    pub fn into_payload<T: DeserializeOwned>(self) -> Result<ResponsePayload<T>, Error> {
+        if !self.status.is_success() {
+            return Err(Error::Status {
+                status: self.status,
+                body: self.body,
+            });
+        }
+
+        let body = self.body;
+        serde_json::from_str::<ResponsePayload<T>>(&body)
+            .map_err(|source| Error::Decode { source, body })?
+            .into_result()
+    }
+}
 
 #[derive(Clone)]
 pub struct Client {
     pub(crate) base_url: String,
+    profile: DeviceProfile,
+    retry: RetryPolicy,
+    limiter: Option<RateLimiter>,
     client: reqwest::Client,
 }
 
+/// Builder for `Client` with non-default settings.
+///
+/// HTTP settings (timeouts, proxies, headers and certificates) are ignored if preconfigured
+/// reqwest client is passed with `http_client`.
+#[derive(Default)]
+pub struct ClientBuilder {
+    base_url: Option<String>,
+    profile: DeviceProfile,
+    retry: RetryPolicy,
+    rate_limit: Option<(f64, u32)>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxies: Vec<reqwest::Proxy>,
+    headers: HeaderMap,
+    root_certificates: Vec<reqwest::Certificate>,
+    http_client: Option<reqwest::Client>,
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// API url, useful only for testing.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.to_owned());
+        self
+    }
+
+    /// Device profile passed with every request.
+    pub fn profile(mut self, profile: DeviceProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Retry transient failures, nothing is retried by default.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Limit requests rate, so API doesn't respond with `REQUEST_RATE_LIMIT_EXCEEDED`.
+    ///
+    /// Up to `burst` requests are sent at once, then `requests_per_second` on average. The limit
+    /// is shared by all clones of the client. Rate must be positive and finite, otherwise `build`
+    /// fails.
+    pub fn rate_limit(mut self, requests_per_second: f64, burst: u32) -> Self {
+        self.rate_limit = Some((requests_per_second, burst));
+        self
+    }
+
+    /// Shortcut for `User-Agent` of device profile, so set it after `profile`.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.profile.user_agent = Some(user_agent.to_owned());
+        self
+    }
+
+    /// Timeout for connection phase only.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for the whole request, from connection to the end of response body.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// HTTP(S) or SOCKS5 proxy, like `reqwest::Proxy::all("socks5://127.0.0.1:1080")`.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Header passed with every request.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Trust one more root certificate, in addition to the built-in ones.
+    pub fn add_root_certificate(mut self, certificate: reqwest::Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Use preconfigured reqwest client instead of building a new one.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    pub fn build(self) -> Result<Client, Error> {
+        let limiter = match self.rate_limit {
+            Some((rate, _)) if !(rate.is_finite() && rate > 0.0) => {
+                return Err(Error::InvalidOption(format!(
+                    "requests rate must be positive and finite, got {}",
+                    rate
+                )));
+            }
+            Some((rate, burst)) => Some(RateLimiter::new(rate, burst)),
+            None => None,
+        };
+        let client = match self.http_client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder().default_headers(self.headers);
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                for proxy in self.proxies {
+                    builder = builder.proxy(proxy);
+                }
+                for certificate in self.root_certificates {
+                    builder = builder.add_root_certificate(certificate);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(Client {
+            base_url: self.base_url.unwrap_or_else(|| API_URL.to_owned()),
+            profile: self.profile,
+            retry: self.retry,
+            limiter,
+            client,
+        })
+    }
+}
+
 impl Default for Client {
     fn default() -> Self {
         Self::new(API_URL)
@@ -29,38 +217,50 @@ impl Default for Client {
 /// In most cases you don't need to use `::new` method, so instantiate client with `::default`.
 impl Client {
     /// Creates new `Client` with specified API url.
-
-    /// Useful only for testing or working through proxy (maybe).
+    ///
+    /// Useful only for testing, use `builder` to work through proxy.
+    ///
+    /// Panics if HTTP client can't be initialized, like `reqwest::Client::new` does.
     pub fn new(base_url: &str) -> Self {
-        Client {
-            base_url: base_url.to_owned(),
-            client: reqwest::Client::new(),
-        }
+        Self::builder()
+            .base_url(base_url)
+            .build()
+            .expect("HTTP client with default settings must be built")
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    /// Device profile passed with every request.
+    pub fn profile(&self) -> &DeviceProfile {
+        &self.profile
     }
 
     /// Ping bank API for details about specified session and device id.
-    pub async fn ping(&self, device_id: &str, session_id: &str) -> ResponsePayload<UserInfo> {
+    pub async fn ping(
+        &self,
+        device_id: &str,
+        session_id: &str,
+    ) -> Result<ResponsePayload<UserInfo>, Error> {
         self.request(
             "/v1/ping",
             &[("deviceId", device_id), ("sessionid", session_id)],
             &[],
         )
         .await
-        .json()
-        .await
-        .unwrap()
     }
 
     /// Ask bank API for new session.
     ///
     /// This method is a first call in any new interaction with bank API, so you should pregenrate
     /// device id and keep it.
-    pub async fn request_session(&self, device_id: &str) -> ResponsePayload<Session> {
+    pub async fn request_session(
+        &self,
+        device_id: &str,
+    ) -> Result<ResponsePayload<Session>, Error> {
         self.request("/v1/auth/session", &[("deviceId", device_id)], &[])
             .await
-            .json()
-            .await
-            .unwrap()
     }
 
     /// Start auth by phone.
@@ -72,16 +272,13 @@ impl Client {
         device_id: &str,
         session_id: &str,
         phone: &str,
-    ) -> ResponsePayload<Nothing> {
+    ) -> Result<ResponsePayload<Nothing>, Error> {
         self.request(
             "/v1/auth/by/phone",
             &[("deviceId", device_id), ("sessionid", session_id)],
             &[("phone", phone)],
         )
         .await
-        .json()
-        .await
-        .unwrap()
     }
 
     /// Complete auth by phone.
@@ -93,23 +290,57 @@ impl Client {
         session_id: &str,
         operation_ticket: &str,
         sms_code: &str,
-    ) -> ResponsePayload<UserInfo> {
+    ) -> Result<ResponsePayload<UserInfo>, Error> {
+        self.confirm(
+            device_id,
+            session_id,
+            operation_ticket,
+            "auth/by/phone",
+            &ConfirmationData::Sms(sms_code.to_owned()),
+        )
+        .await
+    }
+
+    /// Confirm any operation that is waiting for confirmation.
+    ///
+    /// Operation ticket and initial operation are taken from `ResponsePayload` of the operation,
+    /// use `confirm_pending` to pass them at once. Response type depends on confirmed operation.
+    pub async fn confirm<T: DeserializeOwned>(
+        &self,
+        device_id: &str,
+        session_id: &str,
+        operation_ticket: &str,
+        initial_operation: &str,
+        data: &ConfirmationData,
+    ) -> Result<ResponsePayload<T>, Error> {
         self.request(
-            "/v1/confirm",
+            CONFIRM_PATH,
             &[("deviceId", device_id), ("sessionid", session_id)],
             &[
                 ("initialOperationTicket", operation_ticket),
-                ("initialOperation", "auth/by/phone"),
-                (
-                    "confirmationData",
-                    &serde_json::json!({ "SMSBYID": sms_code }).to_string(),
-                ),
+                ("initialOperation", initial_operation),
+                ("confirmationData", &data.to_json()),
             ],
         )
         .await
-        .json()
+    }
+
+    /// Confirm operation from `ResponsePayload::pending_confirmation`.
+    pub async fn confirm_pending<T: DeserializeOwned>(
+        &self,
+        device_id: &str,
+        session_id: &str,
+        pending: &PendingConfirmation,
+        data: &ConfirmationData,
+    ) -> Result<ResponsePayload<T>, Error> {
+        self.confirm(
+            device_id,
+            session_id,
+            &pending.operation_ticket,
+            &pending.initial_operation,
+            data,
+        )
         .await
-        .unwrap()
     }
 
     /// Auth by password.
@@ -121,16 +352,13 @@ impl Client {
         device_id: &str,
         session_id: &str,
         password: &str,
-    ) -> ResponsePayload<UserInfo> {
+    ) -> Result<ResponsePayload<UserInfo>, Error> {
         self.request(
             "/v1/auth/by/password",
             &[("deviceId", device_id), ("sessionid", session_id)],
             &[("password", password)],
         )
         .await
-        .json()
-        .await
-        .unwrap()
     }
 
     /// Set auth pin like a mobile app does it with pin and fingerprint.
@@ -142,16 +370,13 @@ impl Client {
         device_id: &str,
         session_id: &str,
         pin_hash: &str,
-    ) -> ResponsePayload<Nothing> {
+    ) -> Result<ResponsePayload<Nothing>, Error> {
         self.request(
             "/v1/auth/pin/set",
             &[("deviceId", device_id), ("sessionid", session_id)],
             &[("pinHash", pin_hash)],
         )
         .await
-        .json()
-        .await
-        .unwrap()
     }
 
     /// Auth by pin like a mobile app does it with pin and fingerprint.
@@ -165,7 +390,7 @@ impl Client {
         session_id: &str,
         pin_hash: &str,
         old_session_id: &str,
-    ) -> ResponsePayload<UserInfo> {
+    ) -> Result<ResponsePayload<UserInfo>, Error> {
         self.request(
             "/v1/auth/by/pin",
             &[("deviceId", device_id), ("sessionid", session_id)],
@@ -176,9 +401,6 @@ impl Client {
             ],
         )
         .await
-        .json()
-        .await
-        .unwrap()
     }
 
     /// List all bank accounts as flat list.
@@ -188,16 +410,13 @@ impl Client {
         &self,
         device_id: &str,
         session_id: &str,
-    ) -> ResponsePayload<Vec<Account>> {
+    ) -> Result<ResponsePayload<Vec<Account>>, Error> {
         self.request(
             "/v1/accounts_flat",
             &[("deviceId", device_id), ("sessionid", session_id)],
             &[],
         )
         .await
-        .json()
-        .await
-        .unwrap()
     }
 
     /// List operations for specified account id.
@@ -211,7 +430,7 @@ impl Client {
         account_id: &str,
         start: DateTime<Utc>,
         end: DateTime<Utc>,
-    ) -> ResponsePayload<Vec<Operation>> {
+    ) -> Result<ResponsePayload<Vec<Operation>>, Error> {
         let start = start.timestamp_millis().to_string();
         let end = end.timestamp_millis().to_string();
 
@@ -221,28 +440,208 @@ impl Client {
             &[("account", account_id), ("start", &start), ("end", &end)],
         )
         .await
-        .json()
-        .await
-        .unwrap()
     }
 
-    async fn request(
+    /// List operations for any long interval, like a history of several years.
+    ///
+    /// Interval is split into windows fetched concurrently, see `RangeOptions`. Operations are
+    /// de-duplicated by id and sorted by time.
+    pub async fn list_operations_range(
+        &self,
+        device_id: &str,
+        session_id: &str,
+        account_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        options: &RangeOptions,
+    ) -> Result<Vec<Operation>, Error> {
+        let chunks: Vec<Vec<Operation>> =
+            futures::stream::iter(operations::windows(start, end, options.window)?)
+                .map(|(start, end)| async move {
+                    self.list_operations(device_id, session_id, account_id, start, end)
+                        .await?
+                        .payload
+                        .ok_or(Error::MissingPayload)
+                })
+                .buffer_unordered(options.concurrency.max(1))
+                .try_collect()
+                .await?;
+
+        Ok(operations::dedup_and_sort(
+            chunks.into_iter().flatten().collect(),
+            &mut HashSet::new(),
+        ))
+    }
+
+    /// Yields operations for any long interval lazily, window by window.
+    ///
+    /// Unlike `list_operations_range`, only a few windows (up to `RangeOptions.concurrency`) are
+    /// kept in memory. Operations are sorted by time and de-duplicated on the edges of windows.
+    pub fn operations_stream(
+        &self,
+        device_id: &str,
+        session_id: &str,
+        account_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        options: &RangeOptions,
+    ) -> impl Stream<Item = Result<Operation, Error>> {
+        let client = self.clone();
+        let device_id = device_id.to_owned();
+        let session_id = session_id.to_owned();
+        let account_id = account_id.to_owned();
+
+        // invalid window is yielded as the only error of the stream
+        let windows: Vec<Result<_, Error>> = match operations::windows(start, end, options.window) {
+            Ok(windows) => windows.into_iter().map(Ok).collect(),
+            Err(err) => vec![Err(err)],
+        };
+        let chunks = futures::stream::iter(windows)
+            .map(move |window| {
+                let client = client.clone();
+                let device_id = device_id.clone();
+                let session_id = session_id.clone();
+                let account_id = account_id.clone();
+                async move {
+                    let (start, end) = window?;
+                    client
+                        .list_operations(&device_id, &session_id, &account_id, start, end)
+                        .await?
+                        .payload
+                        .ok_or(Error::MissingPayload)
+                }
+            })
+            .buffered(options.concurrency.max(1));
+
+        // operation on the edge is returned for both windows, so only the previous one is checked
+        let mut previous = HashSet::new();
+        chunks
+            .map(move |chunk| {
+                let items: Vec<Result<Operation, Error>> = match chunk {
+                    Ok(operations) => {
+                        let mut seen = HashSet::new();
+                        let operations = operations
+                            .into_iter()
+                            .filter(|operation| !previous.contains(&operation.id))
+                            .collect();
+                        let operations = operations::dedup_and_sort(operations, &mut seen);
+                        previous = seen;
+                        operations.into_iter().map(Ok).collect()
+                    }
+                    Err(err) => vec![Err(err)],
+                };
+                futures::stream::iter(items)
+            })
+            .flatten()
+    }
+
+    /// Call any API endpoint, even if it isn't wrapped by the crate yet.
+    ///
+    /// Device profile, device id and session id (if any) are passed and retry policy is applied
+    /// like in typed methods. Use `serde_json::Value` as `T` if payload shape is unknown.
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        device_id: &str,
+        session_id: Option<&str>,
+        query: &[(&str, &str)],
+        form: &[(&str, &str)],
+    ) -> Result<ResponsePayload<T>, Error> {
+        let params = call_params(device_id, session_id, query);
+        self.request(path, &params, form).await
+    }
+
+    /// The same as `call`, but returns response with status, headers and body as is.
+    ///
+    /// Response isn't checked, so nothing is retried here.
+    pub async fn call_raw(
+        &self,
+        path: &str,
+        device_id: &str,
+        session_id: Option<&str>,
+        query: &[(&str, &str)],
+        form: &[(&str, &str)],
+    ) -> Result<RawResponse, Error> {
+        let params = call_params(device_id, session_id, query);
+        self.send(path, &params, form).await
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        uri: &str,
+        query: &[(&str, &str)],
+        form: &[(&str, &str)],
+    ) -> Result<ResponsePayload<T>, Error> {
+        let mut attempt = 1;
+        loop {
+            let (result, retry_after) = match self.send(uri, query, form).await {
+                Ok(resp) => {
+                    let retry_after = resp.retry_after();
+                    (resp.into_payload(), retry_after)
+                }
+                Err(err) => (Err(err), None),
+            };
+            match result {
+                Err(err) if uri != CONFIRM_PATH && self.retry.should_retry(attempt, &err) => {
+                    let backoff = self.retry.backoff(attempt);
+                    let delay = retry_after.map_or(backoff, |delay| {
+                        delay.max(backoff).min(self.retry.max_backoff)
+                    });
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                other => return other,
+            }
+        }
+    }
+
+    async fn send(
         &self,
         uri: &str,
         query: &[(&str, &str)],
         form: &[(&str, &str)],
-    ) -> reqwest::Response {
-        self.client
-            .post(&format!("{}{}", self.base_url, uri))
-            .query(&DEFAULT_PARAMS)
+    ) -> Result<RawResponse, Error> {
+        let mut request = self
+            .client
+            .post(format!("{}{}", self.base_url, uri))
+            .query(&self.profile.params())
             .query(query)
-            .form(form)
-            .send()
-            .await
-            .unwrap()
+            .form(form);
+        if let Some(user_agent) = &self.profile.user_agent {
+            request = request.header(reqwest::header::USER_AGENT, user_agent);
+        }
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire().await;
+        }
+        let response = request.send().await?;
+
+        let response = RawResponse {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: response.text().await?,
+        };
+        if let (Some(limiter), Some(delay)) = (&self.limiter, response.retry_after()) {
+            limiter.pause(delay);
+        }
+
+        Ok(response)
     }
 }
 
+fn call_params<'a>(
+    device_id: &'a str,
+    session_id: Option<&'a str>,
+    query: &[(&'a str, &'a str)],
+) -> Vec<(&'a str, &'a str)> {
+    let mut params = vec![("deviceId", device_id)];
+    if let Some(session_id) = session_id {
+        params.push(("sessionid", session_id));
+    }
+    params.extend_from_slice(query);
+
+    params
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,6 +662,38 @@ mod tests {
         let client = Client::new("http://lol.kek");
 
         assert_eq!(client.base_url, "http://lol.kek");
+        assert_eq!(client.profile, DeviceProfile::android());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn request_passes_device_profile(server: MockServer) {
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/example")
+                .header("user-agent", "ultra-agent")
+                .query_param("appVersion", "6.0.0")
+                .query_param("platform", "ios")
+                .query_param("deviceModel", "iPhone13,2")
+                .query_param("osVersion", "14.4");
+            then.status(200);
+        });
+        let profile = DeviceProfile::ios()
+            .with_app_version("6.0.0")
+            .with_user_agent("ultra-agent")
+            .with_device_model("iPhone13,2")
+            .with_os_version("14.4");
+
+        Client::builder()
+            .base_url(&server.base_url())
+            .profile(profile)
+            .build()
+            .unwrap()
+            .request::<Nothing>("/example", &[], &[])
+            .await
+            .ok();
+
+        mock.assert()
     }
 
     #[rstest]
@@ -280,7 +711,10 @@ mod tests {
             then.status(200);
         });
 
-        make_client(&server).request("/example", &[], &[]).await;
+        make_client(&server)
+            .request::<Nothing>("/example", &[], &[])
+            .await
+            .ok();
 
         mock.assert()
     }
@@ -304,8 +738,9 @@ mod tests {
         });
 
         make_client(&server)
-            .request("/example", &[("key1", "val1"), ("key2", "val2")], &[])
-            .await;
+            .request::<Nothing>("/example", &[("key1", "val1"), ("key2", "val2")], &[])
+            .await
+            .ok();
 
         mock.assert()
     }
@@ -328,8 +763,9 @@ mod tests {
         });
 
         make_client(&server)
-            .request("/example", &[], &[("key1", "val1"), ("key2", "val2")])
-            .await;
+            .request::<Nothing>("/example", &[], &[("key1", "val1"), ("key2", "val2")])
+            .await
+            .ok();
 
         mock.assert()
     }
@@ -0,0 +1,49 @@
+/// Data that proves that user confirmed operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmationData {
+    /// Code from SMS.
+    Sms(String),
+    /// Any other confirmation kind, value is passed to API as is.
+    Other {
+        kind: String,
+        value: serde_json::Value,
+    },
+}
+
+impl ConfirmationData {
+    /// Kind of confirmation by code from SMS.
+    pub const SMS: &'static str = "SMSBYID";
+
+    /// Kind of confirmation as it is presented in `ResponsePayload.confirmations`.
+    pub fn kind(&self) -> &str {
+        match self {
+            ConfirmationData::Sms(_) => Self::SMS,
+            ConfirmationData::Other { kind, .. } => kind,
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        let value = match self {
+            ConfirmationData::Sms(code) => serde_json::Value::from(code.as_str()),
+            ConfirmationData::Other { value, .. } => value.clone(),
+        };
+
+        serde_json::json!({ self.kind(): value }).to_string()
+    }
+}
+
+/// Operation that waits for confirmation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingConfirmation {
+    pub operation_ticket: String,
+    pub initial_operation: String,
+    /// Kinds of confirmation accepted by API.
+    pub confirmations: Vec<String>,
+}
+
+impl PendingConfirmation {
+    /// Whether API accepts confirmation of such kind.
+    pub fn accepts(&self, kind: &str) -> bool {
+        self.confirmations.iter().any(|accepted| accepted == kind)
+    }
+}
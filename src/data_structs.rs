@@ -1,17 +1,56 @@
+use crate::confirmation::PendingConfirmation;
+use crate::currency::Currency;
+use crate::error::Error;
 use chrono::{serde::ts_milliseconds, DateTime, Utc};
-use serde::{Deserialize, Deserializer};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::fmt;
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String")]
 pub enum AccessLevel {
-    #[serde(rename = "ANONYMOUS")]
     Anonymous,
-    #[serde(rename = "CANDIDATE")]
     Candidate,
-    #[serde(rename = "CLIENT")]
     Client,
+    /// Any other level that isn't known yet.
+    Unknown(String),
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+impl AccessLevel {
+    /// Returns level as it is presented in API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AccessLevel::Anonymous => "ANONYMOUS",
+            AccessLevel::Candidate => "CANDIDATE",
+            AccessLevel::Client => "CLIENT",
+            AccessLevel::Unknown(value) => value,
+        }
+    }
+}
+
+impl Serialize for AccessLevel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl From<String> for AccessLevel {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "ANONYMOUS" => AccessLevel::Anonymous,
+            "CANDIDATE" => AccessLevel::Candidate,
+            "CLIENT" => AccessLevel::Client,
+            _ => AccessLevel::Unknown(value),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct UserInfo {
     #[serde(rename = "accessLevel")]
     pub access_level: AccessLevel,
@@ -19,25 +58,92 @@ pub struct UserInfo {
     pub user_id: String,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Session {
     #[serde(rename = "sessionid")]
     pub id: String,
     pub ttl: u32,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String")]
 pub enum ResultCode {
-    #[serde(rename = "OK")]
     Ok,
-    #[serde(rename = "WAITING_CONFIRMATION")]
     WaitingConfirmation,
+    /// Session is expired or doesn't have enough access level for requested method.
+    InsufficientPrivileges,
+    InvalidRequestData,
+    RequestRateLimitExceeded,
+    /// Device isn't trusted yet, login from another device requires confirmation.
+    DeviceLinkNeeded,
+    /// Wrong password or pin hash.
+    AuthenticationFailed,
+    /// Wrong confirmation code (from SMS for example).
+    ConfirmationFailed,
+    InternalError,
+    /// Any other code that isn't known yet.
+    Other(String),
+}
+
+impl ResultCode {
+    /// Returns code as it is presented in API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ResultCode::Ok => "OK",
+            ResultCode::WaitingConfirmation => "WAITING_CONFIRMATION",
+            ResultCode::InsufficientPrivileges => "INSUFFICIENT_PRIVILEGES",
+            ResultCode::InvalidRequestData => "INVALID_REQUEST_DATA",
+            ResultCode::RequestRateLimitExceeded => "REQUEST_RATE_LIMIT_EXCEEDED",
+            ResultCode::DeviceLinkNeeded => "DEVICE_LINK_NEEDED",
+            ResultCode::AuthenticationFailed => "AUTHENTICATION_FAILED",
+            ResultCode::ConfirmationFailed => "CONFIRMATION_FAILED",
+            ResultCode::InternalError => "INTERNAL_ERROR",
+            ResultCode::Other(code) => code,
+        }
+    }
+
+    /// Returns `true` for codes of successfully handled requests.
+    pub fn is_success(&self) -> bool {
+        matches!(self, ResultCode::Ok | ResultCode::WaitingConfirmation)
+    }
+}
+
+impl From<String> for ResultCode {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "OK" => ResultCode::Ok,
+            "WAITING_CONFIRMATION" => ResultCode::WaitingConfirmation,
+            "INSUFFICIENT_PRIVILEGES" => ResultCode::InsufficientPrivileges,
+            "INVALID_REQUEST_DATA" => ResultCode::InvalidRequestData,
+            "REQUEST_RATE_LIMIT_EXCEEDED" => ResultCode::RequestRateLimitExceeded,
+            "DEVICE_LINK_NEEDED" => ResultCode::DeviceLinkNeeded,
+            "AUTHENTICATION_FAILED" => ResultCode::AuthenticationFailed,
+            "CONFIRMATION_FAILED" => ResultCode::ConfirmationFailed,
+            "INTERNAL_ERROR" => ResultCode::InternalError,
+            _ => ResultCode::Other(value),
+        }
+    }
+}
+
+impl Serialize for ResultCode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl fmt::Display for ResultCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Nothing {}
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Account {
     #[serde(rename = "externalAccountNumber")]
     pub external_number: String,
@@ -47,91 +153,214 @@ pub struct Account {
     pub money_amount: MoneyAmount,
     pub name: String,
     pub id: String,
+    /// Fields not modeled by the crate yet, as they came from API.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+/// Amount of money in exact decimal representation.
+///
+/// Arithmetic and comparison helpers refuse to mix different currencies. Value is serialized as
+/// a string to keep it exact, but both strings and numbers are accepted on deserialization.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct MoneyAmount {
     pub currency: Currency,
-    pub value: f32,
+    pub value: Decimal,
 }
 
-#[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, PartialEq)]
-pub enum Currency {
-    EUR,
-    RUB,
-    USD,
-    BYN,
-}
+impl MoneyAmount {
+    pub fn new(currency: Currency, value: Decimal) -> Self {
+        MoneyAmount { currency, value }
+    }
 
-impl<'de> Deserialize<'de> for Currency {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        #[derive(Deserialize)]
-        struct Outer {
-            name: Inner,
-        }
+    /// Sum of two amounts of the same currency.
+    pub fn checked_add(&self, other: &MoneyAmount) -> Result<MoneyAmount, Error> {
+        self.ensure_same_currency(other)?;
+        Ok(MoneyAmount::new(
+            self.currency.clone(),
+            self.value + other.value,
+        ))
+    }
 
-        #[allow(clippy::upper_case_acronyms)]
-        #[derive(Deserialize)]
-        enum Inner {
-            RUB,
-            USD,
-            EUR,
-            BYN,
-        }
+    /// Difference of two amounts of the same currency.
+    pub fn checked_sub(&self, other: &MoneyAmount) -> Result<MoneyAmount, Error> {
+        self.ensure_same_currency(other)?;
+        Ok(MoneyAmount::new(
+            self.currency.clone(),
+            self.value - other.value,
+        ))
+    }
 
-        let helper = Outer::deserialize(deserializer)?;
-        Ok(match helper.name {
-            Inner::RUB => Currency::RUB,
-            Inner::USD => Currency::USD,
-            Inner::EUR => Currency::EUR,
-            Inner::BYN => Currency::BYN,
-        })
+    /// Compares two amounts of the same currency.
+    pub fn compare(&self, other: &MoneyAmount) -> Result<Ordering, Error> {
+        self.ensure_same_currency(other)?;
+        Ok(self.value.cmp(&other.value))
+    }
+
+    fn ensure_same_currency(&self, other: &MoneyAmount) -> Result<(), Error> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(Error::CurrencyMismatch {
+                left: self.currency.clone(),
+                right: other.currency.clone(),
+            })
+        }
     }
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
 pub struct ResponsePayload<T> {
     #[serde(rename = "resultCode")]
     pub result_code: ResultCode,
     // exists for success response
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<T>,
     // exists if confirmation required
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub confirmations: Option<Vec<String>>,
-    #[serde(rename = "initialOperation")]
+    #[serde(rename = "initialOperation", skip_serializing_if = "Option::is_none")]
     pub initial_operation: Option<String>,
-    #[serde(rename = "operationTicket")]
+    #[serde(rename = "operationTicket", skip_serializing_if = "Option::is_none")]
     pub operation_ticket: Option<String>,
+    // exist for failed response
+    #[serde(rename = "errorMessage", skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(rename = "plainMessage", skip_serializing_if = "Option::is_none")]
+    pub plain_message: Option<String>,
+}
+
+impl<T> ResponsePayload<T> {
+    /// Turns payload with failed result code into `Error::Api`.
+    ///
+    /// Successful payloads (including ones waiting for confirmation) are returned as is.
+    pub fn into_result(self) -> Result<Self, Error> {
+        if self.result_code.is_success() {
+            Ok(self)
+        } else {
+            Err(Error::Api {
+                result_code: self.result_code,
+                error_message: self.error_message,
+                plain_message: self.plain_message,
+            })
+        }
+    }
+
+    /// Details of the operation if response is waiting for confirmation.
+    pub fn pending_confirmation(&self) -> Option<PendingConfirmation> {
+        if self.result_code != ResultCode::WaitingConfirmation {
+            return None;
+        }
+
+        Some(PendingConfirmation {
+            operation_ticket: self.operation_ticket.clone()?,
+            initial_operation: self.initial_operation.clone()?,
+            confirmations: self.confirmations.clone().unwrap_or_default(),
+        })
+    }
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String")]
 pub enum OperationType {
     Credit,
     Debit,
+    /// Any other type that isn't known yet.
+    Unknown(String),
+}
+
+impl OperationType {
+    /// Returns type as it is presented in API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            OperationType::Credit => "Credit",
+            OperationType::Debit => "Debit",
+            OperationType::Unknown(value) => value,
+        }
+    }
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
+impl Serialize for OperationType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl From<String> for OperationType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Credit" => OperationType::Credit,
+            "Debit" => OperationType::Debit,
+            _ => OperationType::Unknown(value),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String")]
 pub enum OperationGroup {
-    #[serde(rename = "PAY")]
     Pay,
-    #[serde(rename = "INCOME")]
     Income,
-    #[serde(rename = "TRANSFER")]
     Transfer,
-    #[serde(rename = "CASH")]
     Cash,
-    #[serde(rename = "CORRECTION")]
     Correction,
-    #[serde(rename = "CHARGE")]
     Charge,
-    #[serde(rename = "INTERNAL")]
     Internal,
+    /// Any other group that isn't known yet.
+    Unknown(String),
+}
+
+impl OperationGroup {
+    /// Returns group as it is presented in API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            OperationGroup::Pay => "PAY",
+            OperationGroup::Income => "INCOME",
+            OperationGroup::Transfer => "TRANSFER",
+            OperationGroup::Cash => "CASH",
+            OperationGroup::Correction => "CORRECTION",
+            OperationGroup::Charge => "CHARGE",
+            OperationGroup::Internal => "INTERNAL",
+            OperationGroup::Unknown(value) => value,
+        }
+    }
+}
+
+impl Serialize for OperationGroup {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl From<String> for OperationGroup {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "PAY" => OperationGroup::Pay,
+            "INCOME" => OperationGroup::Income,
+            "TRANSFER" => OperationGroup::Transfer,
+            "CASH" => OperationGroup::Cash,
+            "CORRECTION" => OperationGroup::Correction,
+            "CHARGE" => OperationGroup::Charge,
+            "INTERNAL" => OperationGroup::Internal,
+            _ => OperationGroup::Unknown(value),
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+/// Single operation on the account.
+///
+/// Serialized in the same shape as API returns it, so serialized operation can be deserialized
+/// back without loss (operation time keeps milliseconds precision, as in API).
+///
+/// Top-level fields not modeled by the crate yet (cashback, card number, location and so on) are
+/// kept in `extra` as is.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Operation {
     pub id: String,
     pub operation_type: OperationType,
@@ -147,6 +376,7 @@ pub struct Operation {
     pub merchant: Option<String>,
     pub group: OperationGroup,
     pub subgroup: Option<String>,
+    pub extra: Map<String, Value>,
 }
 
 impl<'de> Deserialize<'de> for Operation {
@@ -174,6 +404,8 @@ impl<'de> Deserialize<'de> for Operation {
             merchant: Option<InnerName>,
             group: OperationGroup,
             subgroup: Option<InnerName>,
+            #[serde(flatten)]
+            extra: Map<String, Value>,
         }
 
         #[derive(Deserialize)]
@@ -209,6 +441,77 @@ impl<'de> Deserialize<'de> for Operation {
                 Some(val) => Some(val.name),
                 None => None,
             },
+            extra: helper.extra,
         })
     }
 }
+
+impl Serialize for Operation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct Outer<'a> {
+            id: &'a str,
+            #[serde(rename = "type")]
+            operation_type: &'a OperationType,
+            description: &'a str,
+            amount: &'a MoneyAmount,
+            #[serde(rename = "accountAmount")]
+            account_amount: &'a MoneyAmount,
+            #[serde(rename = "operationTime")]
+            operation_time: InnerTime,
+            #[serde(rename = "spendingCategory")]
+            spending_category: InnerName<'a>,
+            mcc: u16,
+            category: InnerName<'a>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            subcategory: Option<&'a str>,
+            account: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            merchant: Option<InnerName<'a>>,
+            group: &'a OperationGroup,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            subgroup: Option<InnerName<'a>>,
+            #[serde(flatten)]
+            extra: &'a Map<String, Value>,
+        }
+
+        #[derive(Serialize)]
+        struct InnerName<'a> {
+            name: &'a str,
+        }
+
+        #[derive(Serialize)]
+        struct InnerTime {
+            #[serde(with = "ts_milliseconds")]
+            milliseconds: DateTime<Utc>,
+        }
+
+        Outer {
+            id: &self.id,
+            operation_type: &self.operation_type,
+            description: &self.description,
+            amount: &self.amount,
+            account_amount: &self.account_amount,
+            operation_time: InnerTime {
+                milliseconds: self.operation_time,
+            },
+            spending_category: InnerName {
+                name: &self.spending_category,
+            },
+            mcc: self.mcc,
+            category: InnerName {
+                name: &self.category,
+            },
+            subcategory: self.subcategory.as_deref(),
+            account: &self.account,
+            merchant: self.merchant.as_deref().map(|name| InnerName { name }),
+            group: &self.group,
+            subgroup: self.subgroup.as_deref().map(|name| InnerName { name }),
+            extra: &self.extra,
+        }
+        .serialize(serializer)
+    }
+}
@@ -0,0 +1,153 @@
+use crate::currency::Currency;
+use crate::data_structs::ResultCode;
+use std::fmt;
+use std::io;
+
+/// Everything that can go wrong while talking to bank API.
+#[derive(Debug)]
+pub enum Error {
+    /// Request wasn't sent or response wasn't received: connection reset, timeout, DNS, etc.
+    Transport(reqwest::Error),
+    /// API responded with non-success HTTP status.
+    Status {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    /// Response body (or JSON cached locally) can't be decoded into expected structure, raw body
+    /// is attached.
+    Decode {
+        source: serde_json::Error,
+        body: String,
+    },
+    /// API responded, but reported failure with its own result code.
+    Api {
+        result_code: ResultCode,
+        error_message: Option<String>,
+        plain_message: Option<String>,
+    },
+    /// API reported success, but response doesn't contain expected payload.
+    MissingPayload,
+    /// API asks for confirmation of unsupported kind, all requested kinds are attached.
+    UnsupportedConfirmation(Vec<String>),
+    /// Can't read or write stored credentials.
+    Io(io::Error),
+    /// Stored credentials can't be decrypted or decoded: file is corrupted or key is wrong.
+    CorruptedStore,
+    /// Arithmetic or comparison of money amounts in different currencies.
+    CurrencyMismatch { left: Currency, right: Currency },
+    /// Option passed by caller can't be used, description is attached.
+    InvalidOption(String),
+    /// Local cache database failed.
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+    /// Local cache database was created by newer version of the crate.
+    #[cfg(feature = "sqlite")]
+    UnsupportedSchema { version: u32, supported: u32 },
+    /// CSV file can't be written or read.
+    #[cfg(feature = "csv")]
+    Csv(csv::Error),
+    /// CSV file doesn't have required column or its value is empty in some row.
+    #[cfg(feature = "csv")]
+    MissingCsvColumn(&'static str),
+}
+
+impl Error {
+    /// Returns result code reported by API, if any.
+    pub fn result_code(&self) -> Option<&ResultCode> {
+        match self {
+            Error::Api { result_code, .. } => Some(result_code),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if session is expired or doesn't have enough access level.
+    pub fn is_session_expired(&self) -> bool {
+        self.result_code() == Some(&ResultCode::InsufficientPrivileges)
+    }
+
+    /// Returns `true` if provided password, pin hash or confirmation code is wrong.
+    pub fn is_authentication_failed(&self) -> bool {
+        matches!(
+            self.result_code(),
+            Some(ResultCode::AuthenticationFailed) | Some(ResultCode::ConfirmationFailed)
+        )
+    }
+
+    /// Returns `true` if bank asks to slow down.
+    pub fn is_rate_limited(&self) -> bool {
+        self.result_code() == Some(&ResultCode::RequestRateLimitExceeded)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Transport(err) => write!(f, "transport error: {}", err),
+            Error::Status { status, .. } => write!(f, "unexpected HTTP status: {}", status),
+            Error::Decode { source, .. } => write!(f, "can't decode response: {}", source),
+            Error::Api {
+                result_code,
+                error_message: Some(message),
+                ..
+            } => write!(f, "API error {}: {}", result_code, message),
+            Error::Api { result_code, .. } => write!(f, "API error {}", result_code),
+            Error::MissingPayload => write!(f, "response doesn't contain payload"),
+            Error::UnsupportedConfirmation(kinds) => {
+                write!(f, "unsupported confirmation: {}", kinds.join(", "))
+            }
+            Error::Io(err) => write!(f, "IO error: {}", err),
+            Error::CorruptedStore => write!(f, "stored credentials are corrupted or key is wrong"),
+            Error::CurrencyMismatch { left, right } => {
+                write!(f, "can't mix {} and {} amounts", left, right)
+            }
+            Error::InvalidOption(message) => write!(f, "invalid option: {}", message),
+            #[cfg(feature = "sqlite")]
+            Error::Sqlite(err) => write!(f, "cache error: {}", err),
+            #[cfg(feature = "sqlite")]
+            Error::UnsupportedSchema { version, supported } => write!(
+                f,
+                "cache schema version {} is newer than supported {}",
+                version, supported
+            ),
+            #[cfg(feature = "csv")]
+            Error::Csv(err) => write!(f, "CSV error: {}", err),
+            #[cfg(feature = "csv")]
+            Error::MissingCsvColumn(column) => write!(f, "CSV column {} is missing", column),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Transport(err) => Some(err),
+            Error::Decode { source, .. } => Some(source),
+            Error::Io(err) => Some(err),
+            #[cfg(feature = "sqlite")]
+            Error::Sqlite(err) => Some(err),
+            #[cfg(feature = "csv")]
+            Error::Csv(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Transport(err)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for Error {
+    fn from(err: rusqlite::Error) -> Self {
+        Error::Sqlite(err)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<csv::Error> for Error {
+    fn from(err: csv::Error) -> Self {
+        Error::Csv(err)
+    }
+}
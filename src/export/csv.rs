@@ -0,0 +1,216 @@
+use crate::currency::Currency;
+use crate::data_structs::{MoneyAmount, Operation, OperationGroup, OperationType};
+use crate::error::Error;
+use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Map;
+use std::io;
+
+/// Column of CSV file, header is the same as `name`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvColumn {
+    Id,
+    /// RFC 3339 with milliseconds, in timezone of `CsvOptions`.
+    Time,
+    Type,
+    Amount,
+    Currency,
+    AccountAmount,
+    AccountCurrency,
+    Category,
+    Subcategory,
+    SpendingCategory,
+    Mcc,
+    Merchant,
+    Group,
+    /// Internal id of the account.
+    Account,
+    Description,
+}
+
+impl CsvColumn {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CsvColumn::Id => "id",
+            CsvColumn::Time => "time",
+            CsvColumn::Type => "type",
+            CsvColumn::Amount => "amount",
+            CsvColumn::Currency => "currency",
+            CsvColumn::AccountAmount => "account_amount",
+            CsvColumn::AccountCurrency => "account_currency",
+            CsvColumn::Category => "category",
+            CsvColumn::Subcategory => "subcategory",
+            CsvColumn::SpendingCategory => "spending_category",
+            CsvColumn::Mcc => "mcc",
+            CsvColumn::Merchant => "merchant",
+            CsvColumn::Group => "group",
+            CsvColumn::Account => "account",
+            CsvColumn::Description => "description",
+        }
+    }
+
+    fn value(&self, operation: &Operation, timezone: &FixedOffset) -> String {
+        match self {
+            CsvColumn::Id => operation.id.clone(),
+            CsvColumn::Time => operation
+                .operation_time
+                .with_timezone(timezone)
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            CsvColumn::Type => operation.operation_type.as_str().to_owned(),
+            CsvColumn::Amount => operation.amount.value.to_string(),
+            CsvColumn::Currency => operation.amount.currency.code().to_owned(),
+            CsvColumn::AccountAmount => operation.account_amount.value.to_string(),
+            CsvColumn::AccountCurrency => operation.account_amount.currency.code().to_owned(),
+            CsvColumn::Category => operation.category.clone(),
+            CsvColumn::Subcategory => operation.subcategory.clone().unwrap_or_default(),
+            CsvColumn::SpendingCategory => operation.spending_category.clone(),
+            CsvColumn::Mcc => operation.mcc.to_string(),
+            CsvColumn::Merchant => operation.merchant.clone().unwrap_or_default(),
+            CsvColumn::Group => operation.group.as_str().to_owned(),
+            CsvColumn::Account => operation.account.clone(),
+            CsvColumn::Description => operation.description.clone(),
+        }
+    }
+}
+
+/// What and how is written to CSV file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    pub columns: Vec<CsvColumn>,
+    /// Timezone of `Time` column, UTC by default.
+    pub timezone: FixedOffset,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            columns: vec![
+                CsvColumn::Id,
+                CsvColumn::Time,
+                CsvColumn::Type,
+                CsvColumn::Amount,
+                CsvColumn::Currency,
+                CsvColumn::AccountAmount,
+                CsvColumn::AccountCurrency,
+                CsvColumn::Category,
+                CsvColumn::SpendingCategory,
+                CsvColumn::Mcc,
+                CsvColumn::Merchant,
+                CsvColumn::Description,
+            ],
+            timezone: FixedOffset::east_opt(0).unwrap(),
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn with_columns(mut self, columns: &[CsvColumn]) -> Self {
+        self.columns = columns.to_vec();
+        self
+    }
+
+    pub fn with_timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = timezone;
+        self
+    }
+}
+
+/// Writes operations as CSV with header.
+pub fn write_csv<W: io::Write>(
+    writer: W,
+    operations: &[Operation],
+    options: &CsvOptions,
+) -> Result<(), Error> {
+    let mut writer = ::csv::Writer::from_writer(writer);
+    writer.write_record(options.columns.iter().map(CsvColumn::name))?;
+    for operation in operations {
+        writer.write_record(
+            options
+                .columns
+                .iter()
+                .map(|column| column.value(operation, &options.timezone)),
+        )?;
+    }
+    writer.flush().map_err(Error::Io)?;
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Record {
+    id: String,
+    time: DateTime<FixedOffset>,
+    #[serde(rename = "type")]
+    operation_type: Option<String>,
+    amount: Decimal,
+    currency: String,
+    account_amount: Option<Decimal>,
+    account_currency: Option<String>,
+    category: Option<String>,
+    subcategory: Option<String>,
+    spending_category: Option<String>,
+    mcc: Option<u16>,
+    merchant: Option<String>,
+    group: Option<String>,
+    account: Option<String>,
+    description: Option<String>,
+}
+
+/// Reads operations written by `write_csv`, columns are matched by header.
+///
+/// `id`, `time`, `amount` and `currency` columns are required and must be filled in every row.
+/// Account amount is the same as amount if it wasn't written, other missing columns are left
+/// empty.
+pub fn read_csv<R: io::Read>(reader: R) -> Result<Vec<Operation>, Error> {
+    let mut reader = ::csv::Reader::from_reader(reader);
+    let headers = reader.headers()?.clone();
+    for column in &[
+        CsvColumn::Id,
+        CsvColumn::Time,
+        CsvColumn::Amount,
+        CsvColumn::Currency,
+    ] {
+        if !headers.iter().any(|header| header == column.name()) {
+            return Err(Error::MissingCsvColumn(column.name()));
+        }
+    }
+
+    reader
+        .deserialize::<Record>()
+        .map(|record| {
+            let record: Record = record?;
+            if record.id.is_empty() {
+                return Err(Error::MissingCsvColumn(CsvColumn::Id.name()));
+            }
+            if record.currency.is_empty() {
+                return Err(Error::MissingCsvColumn(CsvColumn::Currency.name()));
+            }
+            let amount = MoneyAmount::new(Currency::from_code(&record.currency), record.amount);
+            let account_amount = match (record.account_amount, record.account_currency) {
+                (Some(value), Some(currency)) => {
+                    MoneyAmount::new(Currency::from_code(&currency), value)
+                }
+                _ => amount.clone(),
+            };
+
+            Ok(Operation {
+                id: record.id,
+                operation_type: OperationType::from(record.operation_type.unwrap_or_default()),
+                description: record.description.unwrap_or_default(),
+                amount,
+                account_amount,
+                operation_time: record.time.with_timezone(&Utc),
+                spending_category: record.spending_category.unwrap_or_default(),
+                mcc: record.mcc.unwrap_or_default(),
+                category: record.category.unwrap_or_default(),
+                subcategory: record.subcategory,
+                account: record.account.unwrap_or_default(),
+                merchant: record.merchant,
+                group: OperationGroup::from(record.group.unwrap_or_default()),
+                subgroup: None,
+                extra: Map::new(),
+            })
+        })
+        .collect()
+}
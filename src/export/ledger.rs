@@ -0,0 +1,196 @@
+use crate::data_structs::{Operation, OperationType};
+use crate::error::Error;
+use chrono::FixedOffset;
+use std::collections::HashMap;
+use std::io;
+
+/// Settings of ledger, hledger and beancount export: names of accounts on both sides of
+/// transactions.
+///
+/// Counterpart account is looked up by merchant first, then by MCC, then by spending category.
+/// Operations matching nothing go to default expense or income account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerOptions {
+    /// Account of the bank account itself, like `Assets:Tinkoff:Black`.
+    pub asset: String,
+    pub merchants: HashMap<String, String>,
+    pub mccs: HashMap<u16, String>,
+    pub spending_categories: HashMap<String, String>,
+    /// `Expenses:Unknown` by default.
+    pub default_expense: String,
+    /// `Income:Unknown` by default.
+    pub default_income: String,
+    /// Timezone the date is taken in, UTC by default.
+    pub timezone: FixedOffset,
+}
+
+impl LedgerOptions {
+    pub fn new(asset: &str) -> Self {
+        LedgerOptions {
+            asset: asset.to_owned(),
+            merchants: HashMap::new(),
+            mccs: HashMap::new(),
+            spending_categories: HashMap::new(),
+            default_expense: "Expenses:Unknown".to_owned(),
+            default_income: "Income:Unknown".to_owned(),
+            timezone: FixedOffset::east_opt(0).unwrap(),
+        }
+    }
+
+    pub fn merchant(mut self, merchant: &str, account: &str) -> Self {
+        self.merchants
+            .insert(merchant.to_owned(), account.to_owned());
+        self
+    }
+
+    pub fn mcc(mut self, mcc: u16, account: &str) -> Self {
+        self.mccs.insert(mcc, account.to_owned());
+        self
+    }
+
+    pub fn spending_category(mut self, spending_category: &str, account: &str) -> Self {
+        self.spending_categories
+            .insert(spending_category.to_owned(), account.to_owned());
+        self
+    }
+
+    pub fn with_default_expense(mut self, account: &str) -> Self {
+        self.default_expense = account.to_owned();
+        self
+    }
+
+    pub fn with_default_income(mut self, account: &str) -> Self {
+        self.default_income = account.to_owned();
+        self
+    }
+
+    pub fn with_timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Counterpart account of the operation.
+    pub fn account_for(&self, operation: &Operation) -> &str {
+        operation
+            .merchant
+            .as_ref()
+            .and_then(|merchant| self.merchants.get(merchant))
+            .or_else(|| self.mccs.get(&operation.mcc))
+            .or_else(|| self.spending_categories.get(&operation.spending_category))
+            .map(String::as_str)
+            .unwrap_or_else(|| match operation.operation_type {
+                OperationType::Debit => &self.default_expense,
+                _ => &self.default_income,
+            })
+    }
+
+    /// Postings of the bank account and the counterpart.
+    ///
+    /// Bank account gets amount in account currency. If the operation was made in other
+    /// currency, counterpart gets the original amount priced in account currency with `@@`.
+    fn postings(&self, operation: &Operation) -> [(String, String); 2] {
+        let account_amount = &operation.account_amount;
+        let (asset, counterpart, original) = match operation.operation_type {
+            OperationType::Debit => (
+                -account_amount.value,
+                account_amount.value,
+                operation.amount.value,
+            ),
+            _ => (
+                account_amount.value,
+                -account_amount.value,
+                -operation.amount.value,
+            ),
+        };
+        let currency = account_amount.currency.code();
+
+        let counterpart = if operation.amount.currency == account_amount.currency {
+            format!("{} {}", counterpart, currency)
+        } else {
+            format!(
+                "{} {} @@ {} {}",
+                original,
+                operation.amount.currency.code(),
+                account_amount.value,
+                currency
+            )
+        };
+
+        [
+            (self.account_for(operation).to_owned(), counterpart),
+            (self.asset.clone(), format!("{} {}", asset, currency)),
+        ]
+    }
+}
+
+/// Writes operations as ledger journal, it is read by hledger as well.
+///
+/// Payee is the merchant or the description, operation id is written as `id` tag.
+pub fn write_ledger<W: io::Write>(
+    mut writer: W,
+    operations: &[Operation],
+    options: &LedgerOptions,
+) -> Result<(), Error> {
+    let mut out = String::new();
+    for operation in operations {
+        let date = operation.operation_time.with_timezone(&options.timezone);
+        let payee = operation
+            .merchant
+            .as_deref()
+            .unwrap_or(&operation.description);
+
+        out.push_str(&format!(
+            "{} * {}\n",
+            date.format("%Y-%m-%d"),
+            single_line(payee)
+        ));
+        out.push_str(&format!("    ; id: {}\n", operation.id));
+        for (account, amount) in &options.postings(operation) {
+            out.push_str(&format!("    {}  {}\n", account, amount));
+        }
+        out.push('\n');
+    }
+
+    writer.write_all(out.as_bytes()).map_err(Error::Io)
+}
+
+/// Writes operations as beancount transactions.
+///
+/// Merchant is the payee and description is the narration, operation id is written as `id`
+/// metadata. Accounts and commodities aren't opened, that is left to the main ledger file.
+pub fn write_beancount<W: io::Write>(
+    mut writer: W,
+    operations: &[Operation],
+    options: &LedgerOptions,
+) -> Result<(), Error> {
+    let mut out = String::new();
+    for operation in operations {
+        let date = operation.operation_time.with_timezone(&options.timezone);
+
+        out.push_str(&format!("{} *", date.format("%Y-%m-%d")));
+        if let Some(merchant) = &operation.merchant {
+            out.push_str(&format!(" {}", quote(merchant)));
+        }
+        out.push_str(&format!(" {}\n", quote(&operation.description)));
+        out.push_str(&format!("  id: {}\n", quote(&operation.id)));
+        for (account, amount) in &options.postings(operation) {
+            out.push_str(&format!("  {}  {}\n", account, amount));
+        }
+        out.push('\n');
+    }
+
+    writer.write_all(out.as_bytes()).map_err(Error::Io)
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\n', '\r'], " ")
+}
+
+fn quote(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        single_line(value)
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+    )
+}
@@ -0,0 +1,24 @@
+//! Converters of operations to formats of other tools.
+
+#[cfg(feature = "csv")]
+mod csv;
+mod ledger;
+mod ofx;
+mod qif;
+
+#[cfg(feature = "csv")]
+pub use self::csv::{read_csv, write_csv, CsvColumn, CsvOptions};
+pub use ledger::{write_beancount, write_ledger, LedgerOptions};
+pub use ofx::{write_ofx, OfxOptions};
+pub use qif::{write_qif, QifOptions};
+
+/// Whether account group is one of credit cards or credit lines.
+fn is_credit(group: &str) -> bool {
+    group.to_lowercase().contains("кредит")
+}
+
+/// Whether account group is one of savings accounts or deposits.
+fn is_savings(group: &str) -> bool {
+    let group = group.to_lowercase();
+    group.contains("накопительн") || group.contains("вклад")
+}
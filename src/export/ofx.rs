@@ -0,0 +1,165 @@
+use super::{is_credit, is_savings};
+use crate::data_structs::{Account, Operation, OperationType};
+use crate::error::Error;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::io;
+
+/// Settings of OFX statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfxOptions {
+    /// Time of the statement, used as server time and date of the balance.
+    pub as_of: DateTime<Utc>,
+    /// BIC of the bank, Tinkoff by default.
+    pub bank_id: String,
+    /// `CHECKING`, `SAVINGS`, `CREDITLINE` and so on, picked by account group by default.
+    pub account_type: Option<String>,
+}
+
+impl OfxOptions {
+    pub fn new(as_of: DateTime<Utc>) -> Self {
+        OfxOptions {
+            as_of,
+            bank_id: "044525974".to_owned(),
+            account_type: None,
+        }
+    }
+
+    pub fn with_account_type(mut self, account_type: &str) -> Self {
+        self.account_type = Some(account_type.to_owned());
+        self
+    }
+}
+
+/// Writes OFX 2.x bank statement (`STMTRS`) of the account.
+///
+/// Amounts are taken in account currency, original amount in other currency is written as
+/// `ORIGCURRENCY` with conversion rate.
+pub fn write_ofx<W: io::Write>(
+    mut writer: W,
+    account: &Account,
+    operations: &[Operation],
+    options: &OfxOptions,
+) -> Result<(), Error> {
+    let currency = account.money_amount.currency.code();
+    let start = operations
+        .iter()
+        .map(|operation| operation.operation_time)
+        .min()
+        .unwrap_or(options.as_of);
+    let account_type = options
+        .account_type
+        .clone()
+        .unwrap_or_else(|| account_type(&account.group).to_owned());
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+    out.push_str("<?OFX OFXHEADER=\"200\" VERSION=\"202\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>\n");
+    out.push_str("<OFX>\n");
+    out.push_str("  <SIGNONMSGSRSV1>\n    <SONRS>\n");
+    push_status(&mut out, 6);
+    push_tag(&mut out, 6, "DTSERVER", &date(&options.as_of));
+    push_tag(&mut out, 6, "LANGUAGE", "RUS");
+    out.push_str("    </SONRS>\n  </SIGNONMSGSRSV1>\n");
+    out.push_str("  <BANKMSGSRSV1>\n    <STMTTRNRS>\n");
+    push_tag(&mut out, 6, "TRNUID", "0");
+    push_status(&mut out, 6);
+    out.push_str("      <STMTRS>\n");
+    push_tag(&mut out, 8, "CURDEF", currency);
+    out.push_str("        <BANKACCTFROM>\n");
+    push_tag(&mut out, 10, "BANKID", &options.bank_id);
+    push_tag(&mut out, 10, "ACCTID", &account.external_number);
+    push_tag(&mut out, 10, "ACCTTYPE", &account_type);
+    out.push_str("        </BANKACCTFROM>\n");
+    out.push_str("        <BANKTRANLIST>\n");
+    push_tag(&mut out, 10, "DTSTART", &date(&start));
+    push_tag(&mut out, 10, "DTEND", &date(&options.as_of));
+    for operation in operations {
+        push_transaction(&mut out, operation);
+    }
+    out.push_str("        </BANKTRANLIST>\n");
+    out.push_str("        <LEDGERBAL>\n");
+    push_tag(
+        &mut out,
+        10,
+        "BALAMT",
+        &account.money_amount.value.to_string(),
+    );
+    push_tag(&mut out, 10, "DTASOF", &date(&options.as_of));
+    out.push_str("        </LEDGERBAL>\n");
+    out.push_str("      </STMTRS>\n    </STMTTRNRS>\n  </BANKMSGSRSV1>\n</OFX>\n");
+
+    writer.write_all(out.as_bytes()).map_err(Error::Io)
+}
+
+fn push_transaction(out: &mut String, operation: &Operation) {
+    let (kind, amount) = match &operation.operation_type {
+        OperationType::Credit => ("CREDIT", operation.account_amount.value),
+        OperationType::Debit => ("DEBIT", -operation.account_amount.value),
+        OperationType::Unknown(_) => ("OTHER", operation.account_amount.value),
+    };
+    let name = operation
+        .merchant
+        .as_deref()
+        .unwrap_or(&operation.description);
+
+    out.push_str("          <STMTTRN>\n");
+    push_tag(out, 12, "TRNTYPE", kind);
+    push_tag(out, 12, "DTPOSTED", &date(&operation.operation_time));
+    push_tag(out, 12, "TRNAMT", &amount.to_string());
+    push_tag(out, 12, "FITID", &operation.id);
+    if operation.mcc != 0 {
+        push_tag(out, 12, "SIC", &operation.mcc.to_string());
+    }
+    // OFX limits name to 32 characters
+    push_tag(out, 12, "NAME", &name.chars().take(32).collect::<String>());
+    push_tag(out, 12, "MEMO", &operation.description);
+    if operation.amount.currency != operation.account_amount.currency
+        && !operation.amount.value.is_zero()
+    {
+        let rate: Decimal = (operation.account_amount.value / operation.amount.value).round_dp(6);
+        out.push_str("            <ORIGCURRENCY>\n");
+        push_tag(out, 14, "CURRATE", &rate.normalize().to_string());
+        push_tag(out, 14, "CURSYM", operation.amount.currency.code());
+        out.push_str("            </ORIGCURRENCY>\n");
+    }
+    out.push_str("          </STMTTRN>\n");
+}
+
+fn push_status(out: &mut String, indent: usize) {
+    out.push_str(&format!("{:indent$}<STATUS>\n", "", indent = indent));
+    push_tag(out, indent + 2, "CODE", "0");
+    push_tag(out, indent + 2, "SEVERITY", "INFO");
+    out.push_str(&format!("{:indent$}</STATUS>\n", "", indent = indent));
+}
+
+fn push_tag(out: &mut String, indent: usize, tag: &str, value: &str) {
+    out.push_str(&format!(
+        "{:indent$}<{tag}>{value}</{tag}>\n",
+        "",
+        indent = indent,
+        tag = tag,
+        value = escape(value)
+    ));
+}
+
+fn date(value: &DateTime<Utc>) -> String {
+    value.format("%Y%m%d%H%M%S%.3f[0:GMT]").to_string()
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn account_type(group: &str) -> &'static str {
+    if is_savings(group) {
+        "SAVINGS"
+    } else if is_credit(group) {
+        "CREDITLINE"
+    } else {
+        "CHECKING"
+    }
+}
@@ -0,0 +1,101 @@
+use super::is_credit;
+use crate::data_structs::{Account, Operation, OperationType};
+use crate::error::Error;
+use chrono::format::{Item, StrftimeItems};
+use chrono::FixedOffset;
+use std::io;
+
+/// How dates are written to QIF file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QifOptions {
+    /// `chrono` format string, `%m/%d/%Y` by default as most of the tools expect US dates.
+    pub date_format: String,
+    /// Timezone the date is taken in, UTC by default.
+    pub timezone: FixedOffset,
+}
+
+impl Default for QifOptions {
+    fn default() -> Self {
+        QifOptions {
+            date_format: "%m/%d/%Y".to_owned(),
+            timezone: FixedOffset::east_opt(0).unwrap(),
+        }
+    }
+}
+
+impl QifOptions {
+    pub fn with_date_format(mut self, date_format: &str) -> Self {
+        self.date_format = date_format.to_owned();
+        self
+    }
+
+    pub fn with_timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = timezone;
+        self
+    }
+}
+
+/// Writes operations of the account as QIF, `!Type:CCard` for credit cards and `!Type:Bank`
+/// for everything else.
+///
+/// Amounts are taken in account currency, debits are negative. Category goes to `L` field as
+/// `category:subcategory`, merchant is the payee.
+pub fn write_qif<W: io::Write>(
+    mut writer: W,
+    account: &Account,
+    operations: &[Operation],
+    options: &QifOptions,
+) -> Result<(), Error> {
+    if StrftimeItems::new(&options.date_format).any(|item| item == Item::Error) {
+        return Err(Error::InvalidOption(format!(
+            "date format {:?} is malformed",
+            options.date_format
+        )));
+    }
+
+    let mut out = String::new();
+    if is_credit(&account.group) {
+        out.push_str("!Type:CCard\n");
+    } else {
+        out.push_str("!Type:Bank\n");
+    }
+
+    for operation in operations {
+        let amount = match operation.operation_type {
+            OperationType::Debit => -operation.account_amount.value,
+            _ => operation.account_amount.value,
+        };
+        let date = operation
+            .operation_time
+            .with_timezone(&options.timezone)
+            .format(&options.date_format);
+
+        out.push_str(&format!("D{}\n", date));
+        out.push_str(&format!("T{}\n", amount));
+        if let Some(merchant) = &operation.merchant {
+            push_field(&mut out, 'P', merchant);
+        }
+        push_field(&mut out, 'M', &operation.description);
+        match &operation.subcategory {
+            Some(subcategory) => push_field(
+                &mut out,
+                'L',
+                &format!("{}:{}", operation.category, subcategory),
+            ),
+            None => push_field(&mut out, 'L', &operation.category),
+        }
+        out.push_str("^\n");
+    }
+
+    writer.write_all(out.as_bytes()).map_err(Error::Io)
+}
+
+/// Skips empty values, every field must fit single line.
+fn push_field(out: &mut String, code: char, value: &str) {
+    if value.is_empty() {
+        return;
+    }
+    out.push(code);
+    out.push_str(&value.replace(['\n', '\r'], " "));
+    out.push('\n');
+}
@@ -1,8 +1,42 @@
+mod authenticated_client;
 mod client;
+mod confirmation;
+mod currency;
 mod data_structs;
+mod error;
+mod export;
+mod login;
+mod operations;
+mod profile;
+mod rate_limit;
+mod retry;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod store;
+mod sync;
 
-pub use client::Client;
+pub use authenticated_client::AuthenticatedClient;
+pub use client::{Client, ClientBuilder, RawResponse};
+pub use confirmation::{ConfirmationData, PendingConfirmation};
+pub use currency::Currency;
 pub use data_structs::{
-    AccessLevel, Account, Currency, MoneyAmount, Nothing, Operation, OperationGroup, OperationType,
+    AccessLevel, Account, MoneyAmount, Nothing, Operation, OperationGroup, OperationType,
     ResponsePayload, ResultCode, Session, UserInfo,
 };
+pub use error::Error;
+#[cfg(feature = "csv")]
+pub use export::{read_csv, write_csv, CsvColumn, CsvOptions};
+pub use export::{
+    write_beancount, write_ledger, write_ofx, write_qif, LedgerOptions, OfxOptions, QifOptions,
+};
+pub use login::{LoginFlow, LoginStep, Prompt};
+pub use operations::RangeOptions;
+pub use profile::DeviceProfile;
+pub use retry::RetryPolicy;
+pub use rust_decimal::Decimal;
+#[cfg(feature = "sqlite")]
+pub use sqlite::{OperationQuery, SqliteCache};
+pub use store::{Credentials, FileStore, MemoryStore, SessionStore};
+pub use sync::{
+    MemorySyncStateStore, SyncReport, SyncState, SyncStateStore, SyncedOperation, Syncer,
+};
@@ -0,0 +1,187 @@
+use crate::authenticated_client::AuthenticatedClient;
+use crate::client::Client;
+use crate::confirmation::ConfirmationData;
+use crate::data_structs::*;
+use crate::error::Error;
+use std::time::{Duration, Instant};
+
+/// Source of user input required to login, like terminal or bot conversation.
+pub trait Prompt {
+    /// Phone number, like +79998887766.
+    fn phone(&mut self) -> String;
+
+    /// Code from SMS sent to the phone.
+    fn sms_code(&mut self) -> String;
+
+    fn password(&mut self) -> String;
+}
+
+/// Next action of the login flow.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoginStep {
+    RequestSession,
+    AuthByPhone,
+    ConfirmPhone { operation_ticket: String },
+    AuthByPassword,
+    SetPin,
+    Done,
+}
+
+/// Drives login from scratch to the session with `AccessLevel::Client`.
+///
+/// Sequence is `request_session` → `auth_by_phone` → `confirm_auth_by_phone` →
+/// `auth_by_password` → `set_auth_pin`, but steps are picked by the access level of the session,
+/// so unnecessary ones are skipped. Failed step (wrong SMS code or password) can be repeated with
+/// another `next` call.
+pub struct LoginFlow {
+    client: Client,
+    device_id: String,
+    pin_hash: Option<String>,
+    session_id: String,
+    expires_at: Option<Instant>,
+    user_info: Option<UserInfo>,
+    step: LoginStep,
+}
+
+impl LoginFlow {
+    pub fn new(client: Client, device_id: &str) -> Self {
+        LoginFlow {
+            client,
+            device_id: device_id.to_owned(),
+            pin_hash: None,
+            session_id: String::new(),
+            expires_at: None,
+            user_info: None,
+            step: LoginStep::RequestSession,
+        }
+    }
+
+    /// Set auth pin at the end of the flow, so session can be renewed by pin hash later.
+    pub fn with_pin_hash(mut self, pin_hash: &str) -> Self {
+        self.pin_hash = Some(pin_hash.to_owned());
+        self
+    }
+
+    /// Step that will be performed by the next `next` call.
+    pub fn step(&self) -> &LoginStep {
+        &self.step
+    }
+
+    /// Performs current step, asking prompt for input if needed, and returns the next one.
+    pub async fn next(&mut self, prompt: &mut dyn Prompt) -> Result<&LoginStep, Error> {
+        self.step = match self.step.clone() {
+            LoginStep::RequestSession => {
+                let session = self
+                    .client
+                    .request_session(&self.device_id)
+                    .await?
+                    .payload
+                    .ok_or(Error::MissingPayload)?;
+                self.session_id = session.id;
+                self.expires_at = Some(Instant::now() + Duration::from_secs(session.ttl.into()));
+                self.refresh_user_info().await?
+            }
+            LoginStep::AuthByPhone => {
+                let resp = self
+                    .client
+                    .auth_by_phone(&self.device_id, &self.session_id, &prompt.phone())
+                    .await?;
+                match resp.pending_confirmation() {
+                    Some(pending) if pending.accepts(ConfirmationData::SMS) => {
+                        LoginStep::ConfirmPhone {
+                            operation_ticket: pending.operation_ticket,
+                        }
+                    }
+                    Some(pending) => {
+                        return Err(Error::UnsupportedConfirmation(pending.confirmations))
+                    }
+                    None => self.refresh_user_info().await?,
+                }
+            }
+            LoginStep::ConfirmPhone { operation_ticket } => {
+                let user_info = self
+                    .client
+                    .confirm_auth_by_phone(
+                        &self.device_id,
+                        &self.session_id,
+                        &operation_ticket,
+                        &prompt.sms_code(),
+                    )
+                    .await?
+                    .payload
+                    .ok_or(Error::MissingPayload)?;
+                self.remember_user_info(user_info)
+            }
+            LoginStep::AuthByPassword => {
+                let user_info = self
+                    .client
+                    .auth_by_password(&self.device_id, &self.session_id, &prompt.password())
+                    .await?
+                    .payload
+                    .ok_or(Error::MissingPayload)?;
+                self.remember_user_info(user_info)
+            }
+            LoginStep::SetPin => {
+                if let Some(pin_hash) = &self.pin_hash {
+                    self.client
+                        .set_auth_pin(&self.device_id, &self.session_id, pin_hash)
+                        .await?;
+                }
+                LoginStep::Done
+            }
+            LoginStep::Done => LoginStep::Done,
+        };
+
+        Ok(&self.step)
+    }
+
+    /// Performs all steps until session gets full access.
+    pub async fn run(mut self, prompt: &mut dyn Prompt) -> Result<AuthenticatedClient, Error> {
+        while self.step != LoginStep::Done {
+            self.next(prompt).await?;
+        }
+
+        Ok(self.into_client())
+    }
+
+    fn into_client(self) -> AuthenticatedClient {
+        let user_info = self.user_info.unwrap_or(UserInfo {
+            access_level: AccessLevel::Anonymous,
+            user_id: String::new(),
+        });
+
+        AuthenticatedClient::from_parts(
+            self.client,
+            self.device_id,
+            self.session_id,
+            user_info,
+            self.pin_hash,
+            self.expires_at,
+        )
+    }
+
+    async fn refresh_user_info(&mut self) -> Result<LoginStep, Error> {
+        let user_info = self
+            .client
+            .ping(&self.device_id, &self.session_id)
+            .await?
+            .payload
+            .ok_or(Error::MissingPayload)?;
+
+        Ok(self.remember_user_info(user_info))
+    }
+
+    fn remember_user_info(&mut self, user_info: UserInfo) -> LoginStep {
+        let step = match user_info.access_level {
+            AccessLevel::Anonymous => LoginStep::AuthByPhone,
+            AccessLevel::Candidate => LoginStep::AuthByPassword,
+            AccessLevel::Client if self.pin_hash.is_some() => LoginStep::SetPin,
+            AccessLevel::Client => LoginStep::Done,
+            // nothing is known about next steps, so let caller decide by access level
+            AccessLevel::Unknown(_) => LoginStep::Done,
+        };
+        self.user_info = Some(user_info);
+
+        step
+    }
+}
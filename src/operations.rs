@@ -0,0 +1,83 @@
+use crate::data_structs::Operation;
+use crate::error::Error;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+
+/// How long intervals are split into windows for `Client::list_operations_range` and
+/// `Client::operations_stream`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeOptions {
+    /// Size of the window fetched by single `list_operations` call.
+    pub window: Duration,
+    /// How many windows are fetched at once.
+    pub concurrency: usize,
+}
+
+impl Default for RangeOptions {
+    fn default() -> Self {
+        RangeOptions {
+            window: Duration::days(30),
+            concurrency: 4,
+        }
+    }
+}
+
+impl RangeOptions {
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+}
+
+/// Start and end of the window.
+type Window = (DateTime<Utc>, DateTime<Utc>);
+
+/// Splits interval into consecutive windows, the last one may be shorter.
+pub(crate) fn windows(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    window: Duration,
+) -> Result<Vec<Window>, Error> {
+    if window <= Duration::zero() {
+        return Err(Error::InvalidOption(format!(
+            "window must be positive, got {}",
+            window
+        )));
+    }
+
+    let mut windows = vec![];
+    let mut window_start = start;
+    while window_start < end {
+        // window too large to be added is longer than any interval
+        let window_end = window_start
+            .checked_add_signed(window)
+            .map_or(end, |window_end| window_end.min(end));
+        windows.push((window_start, window_end));
+        window_start = window_end;
+    }
+
+    Ok(windows)
+}
+
+/// Removes operations with already seen ids and sorts the rest by time.
+pub(crate) fn dedup_and_sort(
+    operations: Vec<Operation>,
+    seen: &mut HashSet<String>,
+) -> Vec<Operation> {
+    let mut operations: Vec<Operation> = operations
+        .into_iter()
+        .filter(|operation| seen.insert(operation.id.clone()))
+        .collect();
+    operations.sort_by(|a, b| {
+        a.operation_time
+            .cmp(&b.operation_time)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    operations
+}
@@ -0,0 +1,87 @@
+/// How the client introduces itself to API, like a specific version of the mobile app.
+///
+/// Default profile is the Android app, use presets or change fields to follow app updates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceProfile {
+    pub app_version: String,
+    pub platform: String,
+    pub origin: String,
+    pub app_name: String,
+    pub connection_type: String,
+    pub connection_subtype: String,
+    /// Sent as `User-Agent` header, reqwest doesn't send it by default.
+    pub user_agent: Option<String>,
+    pub device_model: Option<String>,
+    pub os_version: Option<String>,
+}
+
+impl Default for DeviceProfile {
+    fn default() -> Self {
+        Self::android()
+    }
+}
+
+impl DeviceProfile {
+    /// Android mobile app.
+    pub fn android() -> Self {
+        DeviceProfile {
+            app_version: "5.5.1".to_owned(),
+            platform: "android".to_owned(),
+            origin: "mobile,ib5,loyalty,platform".to_owned(),
+            app_name: "mobile".to_owned(),
+            connection_type: "Cellular".to_owned(),
+            connection_subtype: "4G".to_owned(),
+            user_agent: None,
+            device_model: None,
+            os_version: None,
+        }
+    }
+
+    /// iOS mobile app.
+    pub fn ios() -> Self {
+        DeviceProfile {
+            platform: "ios".to_owned(),
+            ..Self::android()
+        }
+    }
+
+    pub fn with_app_version(mut self, app_version: &str) -> Self {
+        self.app_version = app_version.to_owned();
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_owned());
+        self
+    }
+
+    pub fn with_device_model(mut self, device_model: &str) -> Self {
+        self.device_model = Some(device_model.to_owned());
+        self
+    }
+
+    pub fn with_os_version(mut self, os_version: &str) -> Self {
+        self.os_version = Some(os_version.to_owned());
+        self
+    }
+
+    /// Query params passed with every request.
+    pub(crate) fn params(&self) -> Vec<(&str, &str)> {
+        let mut params = vec![
+            ("appVersion", self.app_version.as_str()),
+            ("connectionSubtype", &self.connection_subtype),
+            ("appName", &self.app_name),
+            ("origin", &self.origin),
+            ("connectionType", &self.connection_type),
+            ("platform", &self.platform),
+        ];
+        if let Some(device_model) = &self.device_model {
+            params.push(("deviceModel", device_model));
+        }
+        if let Some(os_version) = &self.os_version {
+            params.push(("osVersion", os_version));
+        }
+
+        params
+    }
+}
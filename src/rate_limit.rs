@@ -0,0 +1,83 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Longest pause, so broken hint of API doesn't hold requests forever.
+const MAX_PAUSE: Duration = Duration::from_secs(60 * 60);
+
+/// Token bucket shared by all clones of `Client`.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    updated_at: Instant,
+    // set by API hints like Retry-After header
+    paused_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(requests_per_second: f64, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        RateLimiter {
+            bucket: Arc::new(Mutex::new(Bucket {
+                rate: requests_per_second,
+                burst,
+                tokens: burst,
+                updated_at: Instant::now(),
+                paused_until: None,
+            })),
+        }
+    }
+
+    /// Waits until request is allowed.
+    pub(crate) async fn acquire(&self) {
+        loop {
+            let wait = self.bucket.lock().unwrap().take();
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+
+    /// Holds all requests for specified time.
+    pub(crate) fn pause(&self, duration: Duration) {
+        let now = Instant::now();
+        let until = now.checked_add(duration.min(MAX_PAUSE)).unwrap_or(now);
+        let mut bucket = self.bucket.lock().unwrap();
+        if bucket.paused_until.is_none_or(|paused| paused < until) {
+            bucket.paused_until = Some(until);
+        }
+    }
+}
+
+impl Bucket {
+    /// Takes a token if there is one, otherwise returns time to wait for it.
+    fn take(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        if let Some(until) = self.paused_until {
+            if until > now {
+                return Some(until - now);
+            }
+            self.paused_until = None;
+        }
+
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.updated_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            // tiny rate gives wait too long for `Duration`, it is endless anyway
+            let wait = (1.0 - self.tokens) / self.rate;
+            Some(Duration::try_from_secs_f64(wait).unwrap_or(Duration::MAX))
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::error::Error;
+use rand::Rng;
+use std::time::Duration;
+
+/// How many times and how often failed requests are repeated.
+///
+/// Only transient failures are retried: connection errors, timeouts, 5xx and 429 responses and
+/// `REQUEST_RATE_LIMIT_EXCEEDED` result code. Delay from `Retry-After` header is respected, but
+/// it is clamped to `max_backoff`. Confirmation requests are never retried, because they aren't
+/// idempotent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    /// The longest delay between attempts, even if API asks to wait longer.
+    pub max_backoff: Duration,
+    /// Randomize backoff, so concurrent clients don't retry at the same moment.
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Exponential backoff from 200ms to 10s with jitter.
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+        }
+    }
+
+    /// Single attempt, it is what `Client` does by default.
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    pub fn without_jitter(mut self) -> Self {
+        self.jitter = false;
+        self
+    }
+
+    /// Delay before the next attempt, `attempt` is the number of the failed one starting from 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self
+            .initial_backoff
+            .checked_mul(exp)
+            .map_or(self.max_backoff, |delay| delay.min(self.max_backoff));
+
+        if self.jitter {
+            rand::thread_rng().gen_range(delay / 2..=delay)
+        } else {
+            delay
+        }
+    }
+
+    pub(crate) fn should_retry(&self, attempt: u32, err: &Error) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        match err {
+            Error::Transport(err) => err.is_connect() || err.is_timeout() || err.is_request(),
+            Error::Status { status, .. } => {
+                status.is_server_error() || *status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            err => err.is_rate_limited(),
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
@@ -0,0 +1,282 @@
+use crate::data_structs::{Account, Operation};
+use crate::error::Error;
+use chrono::{DateTime, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, Connection};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Schema changes, applied in order. Never change already released ones, add a new one instead.
+const MIGRATIONS: &[&str] = &["
+    CREATE TABLE accounts (
+        id TEXT PRIMARY KEY,
+        external_number TEXT NOT NULL,
+        account_group TEXT NOT NULL,
+        name TEXT NOT NULL,
+        currency TEXT NOT NULL,
+        balance TEXT NOT NULL,
+        raw TEXT NOT NULL
+    );
+
+    CREATE TABLE operations (
+        id TEXT PRIMARY KEY,
+        account_id TEXT NOT NULL,
+        operation_time INTEGER NOT NULL,
+        operation_type TEXT NOT NULL,
+        description TEXT NOT NULL,
+        amount TEXT NOT NULL,
+        amount_currency TEXT NOT NULL,
+        account_amount TEXT NOT NULL,
+        account_currency TEXT NOT NULL,
+        spending_category TEXT NOT NULL,
+        category TEXT NOT NULL,
+        subcategory TEXT,
+        mcc INTEGER NOT NULL,
+        merchant TEXT,
+        operation_group TEXT NOT NULL,
+        subgroup TEXT,
+        raw TEXT NOT NULL
+    );
+    CREATE INDEX operations_account_time ON operations (account_id, operation_time);
+    CREATE INDEX operations_category ON operations (category);
+    CREATE INDEX operations_mcc ON operations (mcc);
+"];
+
+/// Filter for operations stored in `SqliteCache`, empty one matches everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationQuery {
+    pub account_id: Option<String>,
+    /// Inclusive.
+    pub start: Option<DateTime<Utc>>,
+    /// Exclusive.
+    pub end: Option<DateTime<Utc>>,
+    pub category: Option<String>,
+    pub mcc: Option<u16>,
+}
+
+impl OperationQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn account(mut self, account_id: &str) -> Self {
+        self.account_id = Some(account_id.to_owned());
+        self
+    }
+
+    pub fn between(mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.start = Some(start);
+        self.end = Some(end);
+        self
+    }
+
+    pub fn category(mut self, category: &str) -> Self {
+        self.category = Some(category.to_owned());
+        self
+    }
+
+    pub fn mcc(mut self, mcc: u16) -> Self {
+        self.mcc = Some(mcc);
+        self
+    }
+}
+
+/// Local copy of accounts and operations, so reports can be built offline.
+///
+/// Rows are keyed by id and replaced on every upsert. Besides normalized columns used for
+/// queries, each row keeps serialized structure as raw JSON, so nothing is lost.
+pub struct SqliteCache {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteCache {
+    /// Opens database file, creating and migrating it if needed.
+    ///
+    /// Fails with `Error::UnsupportedSchema` if the file was migrated by newer crate version.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut connection: Connection) -> Result<Self, Error> {
+        migrate(&mut connection)?;
+        Ok(SqliteCache {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Version of the schema, it is the number of applied migrations.
+    pub fn schema_version(&self) -> Result<u32, Error> {
+        let connection = self.connection.lock().unwrap();
+        Ok(connection.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+    }
+
+    pub fn upsert_accounts(&self, accounts: &[Account]) -> Result<(), Error> {
+        let mut connection = self.connection.lock().unwrap();
+        let tx = connection.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO accounts (id, external_number, account_group, name, currency, balance, raw)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                ON CONFLICT (id) DO UPDATE SET
+                    external_number = excluded.external_number,
+                    account_group = excluded.account_group,
+                    name = excluded.name,
+                    currency = excluded.currency,
+                    balance = excluded.balance,
+                    raw = excluded.raw",
+            )?;
+            for account in accounts {
+                stmt.execute(params![
+                    account.id,
+                    account.external_number,
+                    account.group,
+                    account.name,
+                    account.money_amount.currency.code(),
+                    account.money_amount.value.to_string(),
+                    to_raw(account),
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    pub fn upsert_operations(&self, operations: &[Operation]) -> Result<(), Error> {
+        let mut connection = self.connection.lock().unwrap();
+        let tx = connection.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO operations (
+                    id, account_id, operation_time, operation_type, description, amount,
+                    amount_currency, account_amount, account_currency, spending_category,
+                    category, subcategory, mcc, merchant, operation_group, subgroup, raw
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+                ON CONFLICT (id) DO UPDATE SET
+                    account_id = excluded.account_id,
+                    operation_time = excluded.operation_time,
+                    operation_type = excluded.operation_type,
+                    description = excluded.description,
+                    amount = excluded.amount,
+                    amount_currency = excluded.amount_currency,
+                    account_amount = excluded.account_amount,
+                    account_currency = excluded.account_currency,
+                    spending_category = excluded.spending_category,
+                    category = excluded.category,
+                    subcategory = excluded.subcategory,
+                    mcc = excluded.mcc,
+                    merchant = excluded.merchant,
+                    operation_group = excluded.operation_group,
+                    subgroup = excluded.subgroup,
+                    raw = excluded.raw",
+            )?;
+            for operation in operations {
+                stmt.execute(params![
+                    operation.id,
+                    operation.account,
+                    operation.operation_time.timestamp_millis(),
+                    operation.operation_type.as_str(),
+                    operation.description,
+                    operation.amount.value.to_string(),
+                    operation.amount.currency.code(),
+                    operation.account_amount.value.to_string(),
+                    operation.account_amount.currency.code(),
+                    operation.spending_category,
+                    operation.category,
+                    operation.subcategory,
+                    operation.mcc,
+                    operation.merchant,
+                    operation.group.as_str(),
+                    operation.subgroup,
+                    to_raw(operation),
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    pub fn accounts(&self) -> Result<Vec<Account>, Error> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection.prepare("SELECT raw FROM accounts ORDER BY id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        rows.map(|raw| from_raw(raw?)).collect()
+    }
+
+    /// Operations matching the query, sorted by time.
+    pub fn operations(&self, query: &OperationQuery) -> Result<Vec<Operation>, Error> {
+        let mut conditions = vec![];
+        let mut values: Vec<Value> = vec![];
+        if let Some(account_id) = &query.account_id {
+            conditions.push("account_id = ?");
+            values.push(account_id.clone().into());
+        }
+        if let Some(start) = query.start {
+            conditions.push("operation_time >= ?");
+            values.push(start.timestamp_millis().into());
+        }
+        if let Some(end) = query.end {
+            conditions.push("operation_time < ?");
+            values.push(end.timestamp_millis().into());
+        }
+        if let Some(category) = &query.category {
+            conditions.push("category = ?");
+            values.push(category.clone().into());
+        }
+        if let Some(mcc) = query.mcc {
+            conditions.push("mcc = ?");
+            values.push(i64::from(mcc).into());
+        }
+
+        let mut sql = "SELECT raw FROM operations".to_owned();
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(" ORDER BY operation_time, id");
+
+        let connection = self.connection.lock().unwrap();
+        let mut stmt = connection.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), |row| {
+            row.get::<_, String>(0)
+        })?;
+
+        rows.map(|raw| from_raw(raw?)).collect()
+    }
+}
+
+fn migrate(connection: &mut Connection) -> Result<(), Error> {
+    let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    // schema of newer crate may be anything, so it isn't touched
+    if version > MIGRATIONS.len() {
+        return Err(Error::UnsupportedSchema {
+            version: version as u32,
+            supported: MIGRATIONS.len() as u32,
+        });
+    }
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = connection.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+fn to_raw<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("data structures are always serializable")
+}
+
+fn from_raw<T: DeserializeOwned>(raw: String) -> Result<T, Error> {
+    serde_json::from_str(&raw).map_err(|source| Error::Decode { source, body: raw })
+}
@@ -0,0 +1,122 @@
+use crate::error::Error;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const NONCE_SIZE: usize = 12;
+
+/// Everything you need to resume work with API without SMS re-auth.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Credentials {
+    pub device_id: String,
+    pub session_id: Option<String>,
+    pub pin_hash: Option<String>,
+    pub user_id: Option<String>,
+}
+
+impl Credentials {
+    pub fn new(device_id: &str) -> Self {
+        Credentials {
+            device_id: device_id.to_owned(),
+            session_id: None,
+            pin_hash: None,
+            user_id: None,
+        }
+    }
+}
+
+/// Storage for credentials between runs.
+pub trait SessionStore {
+    /// Returns previously saved credentials or `None` if nothing was saved yet.
+    fn load(&self) -> Result<Option<Credentials>, Error>;
+
+    /// Replaces saved credentials.
+    fn save(&self, credentials: &Credentials) -> Result<(), Error>;
+}
+
+/// Keeps credentials in memory, so they live as long as the store does.
+#[derive(Default)]
+pub struct MemoryStore {
+    credentials: Mutex<Option<Credentials>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SessionStore for MemoryStore {
+    fn load(&self) -> Result<Option<Credentials>, Error> {
+        Ok(self.credentials.lock().unwrap().clone())
+    }
+
+    fn save(&self, credentials: &Credentials) -> Result<(), Error> {
+        *self.credentials.lock().unwrap() = Some(credentials.clone());
+        Ok(())
+    }
+}
+
+/// Keeps credentials in a file as JSON encrypted with ChaCha20-Poly1305.
+///
+/// Key is 32 bytes long, keep it somewhere else (keyring, env, secrets manager). Use
+/// `FileStore::generate_key` to make a new one.
+pub struct FileStore {
+    path: PathBuf,
+    cipher: ChaCha20Poly1305,
+}
+
+impl FileStore {
+    pub fn new(path: impl Into<PathBuf>, key: &[u8; 32]) -> Self {
+        FileStore {
+            path: path.into(),
+            cipher: ChaCha20Poly1305::new(Key::from_slice(key)),
+        }
+    }
+
+    /// Generates new random key.
+    pub fn generate_key() -> [u8; 32] {
+        ChaCha20Poly1305::generate_key(&mut OsRng).into()
+    }
+}
+
+impl SessionStore for FileStore {
+    fn load(&self) -> Result<Option<Credentials>, Error> {
+        let content = match fs::read(&self.path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(Error::Io(err)),
+        };
+        if content.len() < NONCE_SIZE {
+            return Err(Error::CorruptedStore);
+        }
+
+        let (nonce, ciphertext) = content.split_at(NONCE_SIZE);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| Error::CorruptedStore)?;
+
+        serde_json::from_slice(&plaintext)
+            .map(Some)
+            .map_err(|_| Error::CorruptedStore)
+    }
+
+    fn save(&self, credentials: &Credentials) -> Result<(), Error> {
+        let plaintext = serde_json::to_vec(credentials).expect("credentials are serializable");
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .expect("credentials fit into single message");
+
+        // write to temporary file first, so crash in the middle doesn't destroy saved credentials
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, [nonce.as_slice(), &ciphertext].concat()).map_err(Error::Io)?;
+        fs::rename(&tmp_path, &self.path).map_err(Error::Io)
+    }
+}
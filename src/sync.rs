@@ -0,0 +1,212 @@
+use crate::client::Client;
+use crate::data_structs::Operation;
+use crate::error::Error;
+use crate::operations::RangeOptions;
+use chrono::{serde::ts_milliseconds, serde::ts_milliseconds_option, DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// What is known about account after the last sync.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SyncState {
+    /// Time of the latest synced operation.
+    #[serde(with = "ts_milliseconds_option")]
+    pub high_water_mark: Option<DateTime<Utc>>,
+    /// Operations within overlap margin by id, they are fetched again with the next sync.
+    pub recent: BTreeMap<String, SyncedOperation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SyncedOperation {
+    #[serde(with = "ts_milliseconds")]
+    pub operation_time: DateTime<Utc>,
+    /// Hash of serialized operation, changes with any field of the operation.
+    pub fingerprint: u64,
+}
+
+/// Storage for sync state of every account between runs.
+pub trait SyncStateStore {
+    /// Returns previously saved state or `None` if account wasn't synced yet.
+    fn load(&self, account_id: &str) -> Result<Option<SyncState>, Error>;
+
+    /// Replaces saved state.
+    fn save(&self, account_id: &str, state: &SyncState) -> Result<(), Error>;
+}
+
+/// Keeps sync state in memory, so it lives as long as the store does.
+#[derive(Default)]
+pub struct MemorySyncStateStore {
+    states: Mutex<HashMap<String, SyncState>>,
+}
+
+impl MemorySyncStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SyncStateStore for MemorySyncStateStore {
+    fn load(&self, account_id: &str) -> Result<Option<SyncState>, Error> {
+        Ok(self.states.lock().unwrap().get(account_id).cloned())
+    }
+
+    fn save(&self, account_id: &str, state: &SyncState) -> Result<(), Error> {
+        self.states
+            .lock()
+            .unwrap()
+            .insert(account_id.to_owned(), state.clone());
+        Ok(())
+    }
+}
+
+/// Difference between the last and the current sync.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    pub added: Vec<Operation>,
+    pub changed: Vec<Operation>,
+    /// Ids of operations that API doesn't return anymore, like cancelled authorizations.
+    pub disappeared: Vec<String>,
+}
+
+/// Fetches only operations that are new since the last sync.
+///
+/// Recent operations may change or disappear (pending authorizations are settled or cancelled),
+/// so operations within overlap margin before the latest synced one are fetched again and
+/// compared with the previous sync.
+pub struct Syncer {
+    client: Client,
+    device_id: String,
+    session_id: String,
+    overlap: Duration,
+    initial_start: DateTime<Utc>,
+    options: RangeOptions,
+}
+
+impl Syncer {
+    pub fn new(client: Client, device_id: &str, session_id: &str) -> Self {
+        Syncer {
+            client,
+            device_id: device_id.to_owned(),
+            session_id: session_id.to_owned(),
+            overlap: Duration::days(7),
+            initial_start: Utc::now() - Duration::days(365),
+            options: RangeOptions::default(),
+        }
+    }
+
+    /// How far operations are fetched again before the latest synced one, 7 days by default.
+    ///
+    /// If it reaches beyond representable time, sync starts from initial start again.
+    pub fn with_overlap(mut self, overlap: Duration) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Where the first sync of account starts, a year ago by default.
+    pub fn with_initial_start(mut self, initial_start: DateTime<Utc>) -> Self {
+        self.initial_start = initial_start;
+        self
+    }
+
+    pub fn with_range_options(mut self, options: RangeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Syncs account up to now and saves the new state.
+    pub async fn sync(
+        &self,
+        account_id: &str,
+        store: &dyn SyncStateStore,
+    ) -> Result<SyncReport, Error> {
+        let state = store.load(account_id)?.unwrap_or_default();
+        let start = state
+            .high_water_mark
+            .and_then(|mark| mark.checked_sub_signed(self.overlap))
+            .unwrap_or(self.initial_start);
+
+        let operations = self
+            .client
+            .list_operations_range(
+                &self.device_id,
+                &self.session_id,
+                account_id,
+                start,
+                Utc::now(),
+                &self.options,
+            )
+            .await?;
+
+        let (report, state) = self.compare(state, start, operations);
+        store.save(account_id, &state)?;
+
+        Ok(report)
+    }
+
+    fn compare(
+        &self,
+        previous: SyncState,
+        start: DateTime<Utc>,
+        operations: Vec<Operation>,
+    ) -> (SyncReport, SyncState) {
+        let mut report = SyncReport::default();
+        let mut fetched = BTreeMap::new();
+        for operation in operations {
+            let synced = SyncedOperation {
+                operation_time: operation.operation_time,
+                fingerprint: fingerprint(&operation),
+            };
+            match previous.recent.get(&operation.id) {
+                None => report.added.push(operation.clone()),
+                Some(known) if known.fingerprint != synced.fingerprint => {
+                    report.changed.push(operation.clone())
+                }
+                Some(_) => (),
+            }
+            fetched.insert(operation.id, synced);
+        }
+        report.disappeared = previous
+            .recent
+            .iter()
+            .filter(|(id, known)| known.operation_time >= start && !fetched.contains_key(*id))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let high_water_mark = fetched
+            .values()
+            .map(|synced| synced.operation_time)
+            .chain(previous.high_water_mark)
+            .max();
+        let recent = match high_water_mark {
+            Some(mark) => {
+                // overlap longer than representable time keeps everything
+                let threshold = mark
+                    .checked_sub_signed(self.overlap)
+                    .unwrap_or(DateTime::<Utc>::MIN_UTC);
+                fetched
+                    .into_iter()
+                    .filter(|(_, synced)| synced.operation_time >= threshold)
+                    .collect()
+            }
+            None => BTreeMap::new(),
+        };
+
+        (
+            report,
+            SyncState {
+                high_water_mark,
+                recent,
+            },
+        )
+    }
+}
+
+/// FNV-1a of serialized operation, it is stable between runs unlike `DefaultHasher`.
+fn fingerprint(operation: &Operation) -> u64 {
+    let serialized = serde_json::to_vec(operation).expect("operation is always serializable");
+
+    serialized.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -26,7 +26,8 @@ async fn returns_user_info(server: MockServer) {
 
     let got = make_client(&server)
         .auth_by_password("ultra-device-id", "ultra-session-id", "ultra-password")
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(
         got,
@@ -39,6 +40,8 @@ async fn returns_user_info(server: MockServer) {
             confirmations: None,
             initial_operation: None,
             operation_ticket: None,
+            error_message: None,
+            plain_message: None,
         }
     )
 }
@@ -59,7 +62,8 @@ async fn passes_params(server: MockServer) {
 
     make_client(&server)
         .auth_by_password("ultra-device-id", "ultra-session-id", "ultra-password")
-        .await;
+        .await
+        .unwrap();
 
     mock.assert()
 }
@@ -26,7 +26,8 @@ async fn returns_confirmation_details(server: MockServer) {
 
     let got = make_client(&server)
         .auth_by_phone("ultra-device-id", "ultra-session-id", "+79991112233")
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(
         got,
@@ -36,6 +37,8 @@ async fn returns_confirmation_details(server: MockServer) {
             confirmations: Some(vec!["SMSBYID".to_owned()]),
             initial_operation: Some("auth/by/phone".to_owned()),
             operation_ticket: Some("operation-ticket-example".to_owned()),
+            error_message: None,
+            plain_message: None,
         }
     )
 }
@@ -56,7 +59,8 @@ async fn passes_params(server: MockServer) {
 
     make_client(&server)
         .auth_by_phone("ultra-device-id", "ultra-session-id", "+79991112233")
-        .await;
+        .await
+        .unwrap();
 
     mock.assert()
 }
@@ -30,7 +30,8 @@ async fn returns_user_info(server: MockServer) {
             "ultra-hash",
             "ultra-old-session-id",
         )
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(
         got,
@@ -43,6 +44,8 @@ async fn returns_user_info(server: MockServer) {
             confirmations: None,
             initial_operation: None,
             operation_ticket: None,
+            error_message: None,
+            plain_message: None,
         }
     )
 }
@@ -68,7 +71,8 @@ async fn passes_params(server: MockServer) {
             "ultra-hash",
             "ultra-old-session-id",
         )
-        .await;
+        .await
+        .unwrap();
 
     mock.assert()
 }
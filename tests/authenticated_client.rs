@@ -0,0 +1,242 @@
+use chrono::{DateTime, Utc};
+use httpmock::MockServer;
+use rstest::*;
+use tinkoff_bank::{AccessLevel, AuthenticatedClient, Client};
+
+const CANDIDATE: &str = "{\"resultCode\": \"OK\", \"payload\": {\"accessLevel\": \"CANDIDATE\", \"userId\": \"1234\"}, \"trackingId\": \"AZAZA11\"}";
+const CLIENT: &str = "{\"resultCode\": \"OK\", \"payload\": {\"accessLevel\": \"CLIENT\", \"userId\": \"1234\"}, \"trackingId\": \"AZAZA11\"}";
+const ACCOUNTS: &str = "{\"payload\": [], \"resultCode\": \"OK\", \"trackingId\": \"AZAZA11\"}";
+const SESSION: &str = "{\"payload\": {\"sessionid\": \"ultra-new-session-id\", \"ttl\": 0}, \"resultCode\": \"OK\", \"trackingId\": \"AZAZA11\"}";
+const LONG_SESSION: &str = "{\"payload\": {\"sessionid\": \"ultra-new-session-id\", \"ttl\": 9994}, \"resultCode\": \"OK\", \"trackingId\": \"AZAZA11\"}";
+const INSUFFICIENT_PRIVILEGES: &str = "{\"resultCode\": \"INSUFFICIENT_PRIVILEGES\", \"errorMessage\": \"Недостаточно прав\", \"trackingId\": \"AZAZA11\"}";
+
+#[fixture]
+fn server() -> MockServer {
+    MockServer::start()
+}
+
+async fn make_client(server: &MockServer) -> AuthenticatedClient {
+    AuthenticatedClient::new(
+        Client::new(&server.base_url()),
+        "ultra-device-id",
+        "ultra-session-id",
+    )
+    .await
+    .unwrap()
+}
+
+fn dt(value: &str) -> DateTime<Utc> {
+    value.parse::<DateTime<Utc>>().unwrap()
+}
+
+#[rstest]
+#[tokio::test]
+async fn detects_access_level_on_creation(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/ping")
+            .query_param("sessionid", "ultra-session-id")
+            .query_param("deviceId", "ultra-device-id");
+        then.status(200).body(CLIENT);
+    });
+
+    let got = make_client(&server).await;
+
+    mock.assert();
+    assert_eq!(got.device_id(), "ultra-device-id");
+    assert_eq!(got.session_id(), "ultra-session-id");
+    assert_eq!(got.access_level(), &AccessLevel::Client);
+}
+
+#[rstest]
+#[tokio::test]
+async fn ping_refreshes_access_level(server: MockServer) {
+    let mut candidate = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(CANDIDATE);
+    });
+    let mut client = make_client(&server).await;
+    assert_eq!(client.access_level(), &AccessLevel::Candidate);
+    candidate.delete();
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(CLIENT);
+    });
+
+    client.ping().await.unwrap();
+
+    assert_eq!(client.access_level(), &AccessLevel::Client);
+}
+
+#[rstest]
+#[tokio::test]
+async fn passes_credentials_to_list_accounts(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(CLIENT);
+    });
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/accounts_flat")
+            .query_param("sessionid", "ultra-session-id")
+            .query_param("deviceId", "ultra-device-id");
+        then.status(200).body(ACCOUNTS);
+    });
+
+    make_client(&server).await.list_accounts().await.unwrap();
+
+    mock.assert()
+}
+
+#[rstest]
+#[tokio::test]
+async fn passes_credentials_to_list_operations(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(CLIENT);
+    });
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/operations")
+            .query_param("sessionid", "ultra-session-id")
+            .query_param("deviceId", "ultra-device-id")
+            .body("account=100&start=1234567890000&end=1234567990000");
+        then.status(200).body(ACCOUNTS);
+    });
+
+    make_client(&server)
+        .await
+        .list_operations(
+            "100",
+            dt("2009-02-13T23:31:30Z"),
+            dt("2009-02-13T23:33:10Z"),
+        )
+        .await
+        .unwrap();
+
+    mock.assert()
+}
+
+#[rstest]
+#[tokio::test]
+async fn authorizes_new_session_by_pin(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/auth/session");
+        then.status(200).body(LONG_SESSION);
+    });
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/auth/by/pin")
+            .query_param("sessionid", "ultra-new-session-id")
+            .body("pinHash=ultra-hash&oldSessionId=ultra-session-id&auth_type=pin");
+        then.status(200).body(CLIENT);
+    });
+
+    let got = AuthenticatedClient::by_pin(
+        Client::new(&server.base_url()),
+        "ultra-device-id",
+        "ultra-hash",
+        "ultra-session-id",
+    )
+    .await
+    .unwrap();
+
+    mock.assert();
+    assert_eq!(got.session_id(), "ultra-new-session-id");
+    assert_eq!(got.access_level(), &AccessLevel::Client);
+    assert!(!got.is_expired());
+}
+
+#[rstest]
+#[tokio::test]
+async fn renews_session_when_ttl_is_over(server: MockServer) {
+    let session = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/auth/session");
+        then.status(200).body(SESSION);
+    });
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/auth/by/pin");
+        then.status(200).body(CLIENT);
+    });
+    let accounts = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/accounts_flat");
+        then.status(200).body(ACCOUNTS);
+    });
+    let mut client = AuthenticatedClient::by_pin(
+        Client::new(&server.base_url()),
+        "ultra-device-id",
+        "ultra-hash",
+        "ultra-session-id",
+    )
+    .await
+    .unwrap();
+    assert!(client.is_expired());
+
+    client.list_accounts().await.unwrap();
+
+    session.assert_hits(2);
+    accounts.assert_hits(1);
+}
+
+#[rstest]
+#[tokio::test]
+async fn renews_session_and_retries_when_api_reports_expiration(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(CLIENT);
+    });
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/auth/session");
+        then.status(200).body(LONG_SESSION);
+    });
+    let pin = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/auth/by/pin")
+            .body("pinHash=ultra-hash&oldSessionId=ultra-session-id&auth_type=pin");
+        then.status(200).body(CLIENT);
+    });
+    let expired = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/accounts_flat")
+            .query_param("sessionid", "ultra-session-id");
+        then.status(200).body(INSUFFICIENT_PRIVILEGES);
+    });
+    let renewed = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/accounts_flat")
+            .query_param("sessionid", "ultra-new-session-id");
+        then.status(200).body(ACCOUNTS);
+    });
+    let mut client = make_client(&server).await.with_pin_hash("ultra-hash");
+
+    client.list_accounts().await.unwrap();
+
+    expired.assert();
+    pin.assert();
+    renewed.assert();
+    assert_eq!(client.session_id(), "ultra-new-session-id");
+}
+
+#[rstest]
+#[tokio::test]
+async fn returns_expiration_error_without_pin_hash(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(CLIENT);
+    });
+    let session = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/auth/session");
+        then.status(200).body(LONG_SESSION);
+    });
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/accounts_flat");
+        then.status(200).body(INSUFFICIENT_PRIVILEGES);
+    });
+
+    let got = make_client(&server).await.list_accounts().await;
+
+    assert!(got.unwrap_err().is_session_expired());
+    session.assert_hits(0);
+}
@@ -0,0 +1,173 @@
+use httpmock::MockServer;
+use rstest::*;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+use tinkoff_bank::{AuthenticatedClient, Client, Error, ResultCode};
+
+const CLIENT: &str = "{\"resultCode\": \"OK\", \"payload\": {\"accessLevel\": \"CLIENT\", \"userId\": \"1234\"}, \"trackingId\": \"AZAZA11\"}";
+const RESPONSE: &str = "{\"resultCode\": \"OK\", \"payload\": {\"cashback\": 12.5, \"months\": [\"2021-01\", \"2021-02\"]}, \"trackingId\": \"AZAZA11\"}";
+const FAILURE: &str = "{\"resultCode\": \"INVALID_REQUEST_DATA\", \"errorMessage\": \"Неверные данные\", \"trackingId\": \"AZAZA11\"}";
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Cashback {
+    cashback: f64,
+    months: Vec<String>,
+}
+
+#[fixture]
+fn server() -> MockServer {
+    MockServer::start()
+}
+
+fn make_client(server: &MockServer) -> Client {
+    Client::new(&server.base_url())
+}
+
+#[rstest]
+#[tokio::test]
+async fn calls_any_endpoint(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/loyalty/cashback")
+            .query_param("appName", "mobile")
+            .query_param("deviceId", "ultra-device-id")
+            .query_param("sessionid", "ultra-session-id")
+            .query_param("account", "100")
+            .body("year=2021");
+        then.status(200).body(RESPONSE);
+    });
+
+    let got = make_client(&server)
+        .call::<Cashback>(
+            "/v1/loyalty/cashback",
+            "ultra-device-id",
+            Some("ultra-session-id"),
+            &[("account", "100")],
+            &[("year", "2021")],
+        )
+        .await
+        .unwrap();
+
+    mock.assert();
+    assert_eq!(
+        got.payload,
+        Some(Cashback {
+            cashback: 12.5,
+            months: vec!["2021-01".to_owned(), "2021-02".to_owned()],
+        })
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn decodes_unknown_payload_as_json_value(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/loyalty/cashback");
+        then.status(200).body(RESPONSE);
+    });
+
+    let got = make_client(&server)
+        .call::<serde_json::Value>("/v1/loyalty/cashback", "ultra-device-id", None, &[], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        got.payload,
+        Some(json!({"cashback": 12.5, "months": ["2021-01", "2021-02"]}))
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn fails_on_unsuccessful_result_code(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/loyalty/cashback");
+        then.status(200).body(FAILURE);
+    });
+
+    let got = make_client(&server)
+        .call::<serde_json::Value>("/v1/loyalty/cashback", "ultra-device-id", None, &[], &[])
+        .await;
+
+    assert_eq!(
+        got.unwrap_err().result_code(),
+        Some(&ResultCode::InvalidRequestData)
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn returns_raw_response_as_is(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/loyalty/cashback");
+        then.status(503)
+            .header("Retry-After", "10")
+            .body("Service Unavailable");
+    });
+
+    let got = make_client(&server)
+        .call_raw("/v1/loyalty/cashback", "ultra-device-id", None, &[], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(got.status, 503);
+    assert_eq!(got.headers["retry-after"], "10");
+    assert_eq!(got.retry_after(), Some(Duration::from_secs(10)));
+    assert_eq!(got.body, "Service Unavailable");
+    assert!(matches!(
+        got.into_payload::<serde_json::Value>(),
+        Err(Error::Status { .. })
+    ));
+}
+
+#[rstest]
+#[tokio::test]
+async fn caps_huge_retry_after(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/loyalty/cashback");
+        then.status(503)
+            .header("Retry-After", "18446744073709551615")
+            .body("Service Unavailable");
+    });
+
+    let got = make_client(&server)
+        .call_raw("/v1/loyalty/cashback", "ultra-device-id", None, &[], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(got.retry_after(), Some(Duration::from_secs(60 * 60)));
+}
+
+#[rstest]
+#[tokio::test]
+async fn authenticated_client_calls_with_its_session(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(CLIENT);
+    });
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/loyalty/cashback")
+            .query_param("deviceId", "ultra-device-id")
+            .query_param("sessionid", "ultra-session-id")
+            .query_param("account", "100");
+        then.status(200).body(RESPONSE);
+    });
+    let mut client =
+        AuthenticatedClient::new(make_client(&server), "ultra-device-id", "ultra-session-id")
+            .await
+            .unwrap();
+
+    let got = client
+        .call::<Cashback>("/v1/loyalty/cashback", &[("account", "100")], &[])
+        .await
+        .unwrap();
+
+    mock.assert();
+    assert_eq!(got.payload.unwrap().cashback, 12.5);
+}
@@ -0,0 +1,114 @@
+use httpmock::MockServer;
+use reqwest::header::{HeaderName, HeaderValue};
+use rstest::*;
+use std::time::Duration;
+use tinkoff_bank::{Client, Error};
+
+const ANONYMOUS: &str = "{\"resultCode\": \"OK\", \"payload\": {\"accessLevel\": \"ANONYMOUS\", \"userId\": \"1111\"}, \"trackingId\": \"AZAZA11\"}";
+
+#[fixture]
+fn server() -> MockServer {
+    MockServer::start()
+}
+
+#[rstest]
+#[tokio::test]
+async fn fails_on_request_timeout(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200)
+            .body(ANONYMOUS)
+            .delay(Duration::from_secs(2));
+    });
+    let client = Client::builder()
+        .base_url(&server.base_url())
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    let got = client.ping("ultra-device-id", "ultra-session-id").await;
+
+    match got {
+        Err(Error::Transport(err)) => assert!(err.is_timeout()),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn passes_user_agent_and_headers(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/ping")
+            .header("user-agent", "ultra-agent")
+            .header("x-ultra-header", "ultra-value");
+        then.status(200).body(ANONYMOUS);
+    });
+    let client = Client::builder()
+        .base_url(&server.base_url())
+        .user_agent("ultra-agent")
+        .header(
+            HeaderName::from_static("x-ultra-header"),
+            HeaderValue::from_static("ultra-value"),
+        )
+        .build()
+        .unwrap();
+
+    client
+        .ping("ultra-device-id", "ultra-session-id")
+        .await
+        .unwrap();
+
+    mock.assert()
+}
+
+#[rstest]
+#[tokio::test]
+async fn works_through_proxy(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(ANONYMOUS);
+    });
+    let client = Client::builder()
+        .base_url("http://api.tinkoff.invalid")
+        .proxy(reqwest::Proxy::http(server.base_url()).unwrap())
+        .build()
+        .unwrap();
+
+    client
+        .ping("ultra-device-id", "ultra-session-id")
+        .await
+        .unwrap();
+
+    mock.assert()
+}
+
+#[rstest]
+#[tokio::test]
+async fn uses_injected_http_client(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/ping")
+            .header("x-ultra-header", "ultra-value");
+        then.status(200).body(ANONYMOUS);
+    });
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-ultra-header", HeaderValue::from_static("ultra-value"));
+    let client = Client::builder()
+        .base_url(&server.base_url())
+        .http_client(
+            reqwest::Client::builder()
+                .default_headers(headers)
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+
+    client
+        .ping("ultra-device-id", "ultra-session-id")
+        .await
+        .unwrap();
+
+    mock.assert()
+}
@@ -0,0 +1,116 @@
+use httpmock::MockServer;
+use rstest::*;
+use tinkoff_bank::{
+    Client, ConfirmationData, Nothing, PendingConfirmation, ResponsePayload, ResultCode, UserInfo,
+};
+
+const WAITING: &str = "{\"confirmationData\": {\"SMSBYID\": {\"codeLength\": 4, \"codeType\": \"Numeric\", \"confirmationType\": \"SMSBYID\"}}, \"confirmations\": [\"SMSBYID\"], \"initialOperation\": \"transfer/by/phone\", \"operationTicket\": \"ultra-operation-ticket\", \"resultCode\": \"WAITING_CONFIRMATION\", \"trackingId\": \"AZAZA11\"}";
+const RESPONSE: &str = "{\"payload\": {\"key\": \"key-example\"}, \"resultCode\": \"OK\", \"trackingId\": \"AZAZA11\"}";
+
+#[fixture]
+fn server() -> MockServer {
+    MockServer::start()
+}
+
+fn make_client(server: &MockServer) -> Client {
+    Client::new(&server.base_url())
+}
+
+#[rstest(data, expected_body,
+    case(
+        ConfirmationData::Sms("1234".to_owned()),
+        "initialOperationTicket=ultra-operation-ticket&initialOperation=transfer%2Fby%2Fphone&confirmationData=%7B%22SMSBYID%22%3A%221234%22%7D",
+    ),
+    case(
+        ConfirmationData::Other {
+            kind: "PUSH".to_owned(),
+            value: serde_json::json!({"approved": true}),
+        },
+        "initialOperationTicket=ultra-operation-ticket&initialOperation=transfer%2Fby%2Fphone&confirmationData=%7B%22PUSH%22%3A%7B%22approved%22%3Atrue%7D%7D",
+    ),
+)]
+#[tokio::test]
+async fn passes_confirmation_data(data: ConfirmationData, expected_body: &str, server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/confirm")
+            .query_param("sessionid", "ultra-session-id")
+            .query_param("deviceId", "ultra-device-id")
+            .body(expected_body);
+        then.status(200)
+            .header("Content-Type", "applucation/json")
+            .body(RESPONSE);
+    });
+
+    make_client(&server)
+        .confirm::<Nothing>(
+            "ultra-device-id",
+            "ultra-session-id",
+            "ultra-operation-ticket",
+            "transfer/by/phone",
+            &data,
+        )
+        .await
+        .unwrap();
+
+    mock.assert()
+}
+
+#[rstest]
+#[tokio::test]
+async fn confirms_pending_operation(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/confirm")
+            .body("initialOperationTicket=ultra-operation-ticket&initialOperation=transfer%2Fby%2Fphone&confirmationData=%7B%22SMSBYID%22%3A%221234%22%7D");
+        then.status(200)
+            .header("Content-Type", "applucation/json")
+            .body(RESPONSE);
+    });
+    let waiting: ResponsePayload<Nothing> = serde_json::from_str(WAITING).unwrap();
+    let pending = waiting.pending_confirmation().unwrap();
+
+    make_client(&server)
+        .confirm_pending::<Nothing>(
+            "ultra-device-id",
+            "ultra-session-id",
+            &pending,
+            &ConfirmationData::Sms("1234".to_owned()),
+        )
+        .await
+        .unwrap();
+
+    mock.assert()
+}
+
+#[test]
+fn extracts_pending_confirmation() {
+    let payload: ResponsePayload<Nothing> = serde_json::from_str(WAITING).unwrap();
+
+    let got = payload.pending_confirmation();
+
+    assert_eq!(
+        got,
+        Some(PendingConfirmation {
+            operation_ticket: "ultra-operation-ticket".to_owned(),
+            initial_operation: "transfer/by/phone".to_owned(),
+            confirmations: vec!["SMSBYID".to_owned()],
+        })
+    );
+    assert!(got.unwrap().accepts(ConfirmationData::SMS));
+}
+
+#[test]
+fn has_no_pending_confirmation_for_completed_operation() {
+    let payload = ResponsePayload::<UserInfo> {
+        result_code: ResultCode::Ok,
+        payload: None,
+        confirmations: None,
+        initial_operation: None,
+        operation_ticket: None,
+        error_message: None,
+        plain_message: None,
+    };
+
+    assert_eq!(payload.pending_confirmation(), None)
+}
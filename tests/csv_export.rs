@@ -0,0 +1,140 @@
+#![cfg(feature = "csv")]
+
+use chrono::{DateTime, FixedOffset, Utc};
+use rstest::*;
+use rust_decimal_macros::dec;
+use serde_json::Map;
+use tinkoff_bank::{
+    read_csv, write_csv, CsvColumn, CsvOptions, Currency, Error, MoneyAmount, Operation,
+    OperationGroup, OperationType,
+};
+
+fn dt(value: &str) -> DateTime<Utc> {
+    value.parse::<DateTime<Utc>>().unwrap()
+}
+
+#[fixture]
+fn operations() -> Vec<Operation> {
+    vec![
+        Operation {
+            id: "1234567890".to_owned(),
+            operation_type: OperationType::Credit,
+            description: "Яндекс.Еда, \"доставка\"".to_owned(),
+            amount: MoneyAmount::new(Currency::RUB, dec!(1234.50)),
+            account_amount: MoneyAmount::new(Currency::RUB, dec!(1234.50)),
+            operation_time: dt("2021-02-18T09:07:19.123Z"),
+            spending_category: "Рестораны".to_owned(),
+            mcc: 5812,
+            category: "Рестораны".to_owned(),
+            subcategory: None,
+            account: "100".to_owned(),
+            merchant: Some("Яндекс.Еда".to_owned()),
+            group: OperationGroup::Pay,
+            subgroup: None,
+            extra: Map::new(),
+        },
+        Operation {
+            id: "1234567892".to_owned(),
+            operation_type: OperationType::Debit,
+            description: "Amazon".to_owned(),
+            amount: MoneyAmount::new(Currency::USD, dec!(2)),
+            account_amount: MoneyAmount::new(Currency::RUB, dec!(145.3)),
+            operation_time: dt("2021-02-10T17:36:39Z"),
+            spending_category: "Маркетплейсы".to_owned(),
+            mcc: 5942,
+            category: "Другое".to_owned(),
+            subcategory: Some("Amazon".to_owned()),
+            account: "100".to_owned(),
+            merchant: None,
+            group: OperationGroup::Pay,
+            subgroup: None,
+            extra: Map::new(),
+        },
+    ]
+}
+
+fn export(operations: &[Operation], options: &CsvOptions) -> String {
+    let mut buffer = vec![];
+    write_csv(&mut buffer, operations, options).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[rstest]
+fn writes_default_columns(operations: Vec<Operation>) {
+    let got = export(&operations, &CsvOptions::default());
+
+    assert_eq!(
+        got,
+        "id,time,type,amount,currency,account_amount,account_currency,category,spending_category,mcc,merchant,description\n\
+        1234567890,2021-02-18T09:07:19.123Z,Credit,1234.50,RUB,1234.50,RUB,Рестораны,Рестораны,5812,Яндекс.Еда,\"Яндекс.Еда, \"\"доставка\"\"\"\n\
+        1234567892,2021-02-10T17:36:39.000Z,Debit,2,USD,145.3,RUB,Другое,Маркетплейсы,5942,,Amazon\n"
+    )
+}
+
+#[rstest]
+fn writes_chosen_columns_in_timezone(operations: Vec<Operation>) {
+    let options = CsvOptions::default()
+        .with_columns(&[CsvColumn::Time, CsvColumn::Amount, CsvColumn::Merchant])
+        .with_timezone(FixedOffset::east_opt(3 * 3600).unwrap());
+
+    let got = export(&operations[..1], &options);
+
+    assert_eq!(
+        got,
+        "time,amount,merchant\n2021-02-18T12:07:19.123+03:00,1234.50,Яндекс.Еда\n"
+    )
+}
+
+#[rstest]
+fn reads_written_operations_back(operations: Vec<Operation>) {
+    let options = CsvOptions::default()
+        .with_columns(&[
+            CsvColumn::Id,
+            CsvColumn::Time,
+            CsvColumn::Type,
+            CsvColumn::Amount,
+            CsvColumn::Currency,
+            CsvColumn::AccountAmount,
+            CsvColumn::AccountCurrency,
+            CsvColumn::Category,
+            CsvColumn::Subcategory,
+            CsvColumn::SpendingCategory,
+            CsvColumn::Mcc,
+            CsvColumn::Merchant,
+            CsvColumn::Group,
+            CsvColumn::Account,
+            CsvColumn::Description,
+        ])
+        .with_timezone(FixedOffset::east_opt(3 * 3600).unwrap());
+    let exported = export(&operations, &options);
+
+    let got = read_csv(exported.as_bytes()).unwrap();
+
+    assert_eq!(got, operations);
+}
+
+#[test]
+fn fails_without_required_column() {
+    let got = read_csv("id,amount,currency\n1,10,RUB\n".as_bytes());
+
+    assert!(matches!(got, Err(Error::MissingCsvColumn("time"))));
+}
+
+#[test]
+fn fails_on_malformed_value() {
+    let got = read_csv("id,time,amount,currency\n1,yesterday,10,RUB\n".as_bytes());
+
+    assert!(matches!(got, Err(Error::Csv(_))));
+
+    let got = read_csv("id,time,amount,currency\n1,,10,RUB\n".as_bytes());
+
+    assert!(matches!(got, Err(Error::Csv(_))));
+
+    let got = read_csv("id,time,amount,currency\n,2021-02-18T09:07:19Z,10,RUB\n".as_bytes());
+
+    assert!(matches!(got, Err(Error::MissingCsvColumn("id"))));
+
+    let got = read_csv("id,time,amount,currency\n1,2021-02-18T09:07:19Z,10,\n".as_bytes());
+
+    assert!(matches!(got, Err(Error::MissingCsvColumn("currency"))));
+}
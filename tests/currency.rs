@@ -0,0 +1,69 @@
+use rstest::*;
+use tinkoff_bank::Currency;
+
+fn parse(name: &str, code: u16) -> Currency {
+    serde_json::from_str(&format!(
+        "{{\"code\": {}, \"name\": \"{}\", \"strCode\": \"{}\"}}",
+        code, name, code
+    ))
+    .unwrap()
+}
+
+#[rstest(
+    name,
+    code,
+    expected,
+    case("RUB", 643, Currency::RUB),
+    case("USD", 840, Currency::USD),
+    case("EUR", 978, Currency::EUR),
+    case("BYN", 933, Currency::BYN),
+    case("GBP", 826, Currency::GBP),
+    case("CHF", 756, Currency::CHF),
+    case("KZT", 398, Currency::KZT),
+    case("CNY", 156, Currency::CNY),
+    case("TRY", 949, Currency::TRY)
+)]
+fn parses_known_currency(name: &str, code: u16, expected: Currency) {
+    let got = parse(name, code);
+
+    assert_eq!(got, expected);
+    assert_eq!(got.numeric_code(), Some(code));
+    assert_eq!(got.code(), name);
+}
+
+#[test]
+fn keeps_unknown_currency() {
+    let got = parse("HRK", 191);
+
+    assert_eq!(got, Currency::Unknown("HRK".to_owned()));
+    assert_eq!(got.code(), "HRK");
+    assert_eq!(got.numeric_code(), None);
+    assert_eq!(got.minor_units(), None);
+}
+
+#[rstest(
+    currency,
+    expected,
+    case(Currency::RUB, Some(2)),
+    case(Currency::JPY, Some(0)),
+    case(Currency::KWD, Some(3)),
+    case(Currency::CLF, Some(4)),
+    case(Currency::XAU, None)
+)]
+fn knows_minor_units(currency: Currency, expected: Option<u8>) {
+    assert_eq!(currency.minor_units(), expected)
+}
+
+#[rstest(code, expected,
+    case("GBP", Currency::GBP),
+    case("XXX", Currency::XXX),
+    case("???", Currency::Unknown("???".to_owned())),
+)]
+fn finds_currency_by_code(code: &str, expected: Currency) {
+    assert_eq!(Currency::from_code(code), expected)
+}
+
+#[test]
+fn displays_code() {
+    assert_eq!(Currency::CNY.to_string(), "CNY")
+}
@@ -0,0 +1,191 @@
+use httpmock::MockServer;
+use rstest::*;
+use tinkoff_bank::{Client, Error, Nothing, ResponsePayload, ResultCode};
+
+const FAILURE: &str = "{\"resultCode\": \"INSUFFICIENT_PRIVILEGES\", \"errorMessage\": \"Недостаточно прав\", \"plainMessage\": \"Недостаточно прав\", \"trackingId\": \"AZAZA11\"}";
+
+#[fixture]
+fn server() -> MockServer {
+    MockServer::start()
+}
+
+fn make_client(server: &MockServer) -> Client {
+    Client::new(&server.base_url())
+}
+
+#[tokio::test]
+async fn returns_transport_error_if_api_is_unreachable() {
+    let got = Client::new("http://127.0.0.1:1")
+        .ping("ultra-device-id", "ultra-session-id")
+        .await;
+
+    assert!(matches!(got, Err(Error::Transport(_))))
+}
+
+#[rstest]
+#[tokio::test]
+async fn returns_status_error_for_non_success_status(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(502).body("Bad Gateway");
+    });
+
+    let got = make_client(&server)
+        .ping("ultra-device-id", "ultra-session-id")
+        .await;
+
+    match got {
+        Err(Error::Status { status, body }) => {
+            assert_eq!(status, 502);
+            assert_eq!(body, "Bad Gateway");
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn returns_decode_error_with_raw_body(server: MockServer) {
+    let body = "{\"resultCode\": \"OK\", \"payload\": {\"accessLevel\": 42}}";
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200)
+            .header("Content-Type", "applucation/json")
+            .body(body);
+    });
+
+    let got = make_client(&server)
+        .ping("ultra-device-id", "ultra-session-id")
+        .await;
+
+    match got {
+        Err(Error::Decode { body: got_body, .. }) => assert_eq!(got_body, body),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn returns_decode_error_for_malformed_json(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body("<html>maintenance</html>");
+    });
+
+    let got = make_client(&server)
+        .ping("ultra-device-id", "ultra-session-id")
+        .await;
+
+    match got {
+        Err(Error::Decode { body, .. }) => assert_eq!(body, "<html>maintenance</html>"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn returns_api_error_for_failed_result_code(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/accounts_flat");
+        then.status(200)
+            .header("Content-Type", "applucation/json")
+            .body(FAILURE);
+    });
+
+    let got = make_client(&server)
+        .list_accounts("ultra-device-id", "ultra-session-id")
+        .await;
+
+    match got {
+        Err(Error::Api {
+            result_code,
+            error_message,
+            plain_message,
+        }) => {
+            assert_eq!(result_code, ResultCode::InsufficientPrivileges);
+            assert_eq!(error_message, Some("Недостаточно прав".to_owned()));
+            assert_eq!(plain_message, Some("Недостаточно прав".to_owned()));
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[rstest(code, expected,
+    case("INSUFFICIENT_PRIVILEGES", ResultCode::InsufficientPrivileges),
+    case("INVALID_REQUEST_DATA", ResultCode::InvalidRequestData),
+    case("REQUEST_RATE_LIMIT_EXCEEDED", ResultCode::RequestRateLimitExceeded),
+    case("DEVICE_LINK_NEEDED", ResultCode::DeviceLinkNeeded),
+    case("AUTHENTICATION_FAILED", ResultCode::AuthenticationFailed),
+    case("CONFIRMATION_FAILED", ResultCode::ConfirmationFailed),
+    case("INTERNAL_ERROR", ResultCode::InternalError),
+    case("SOMETHING_NEW", ResultCode::Other("SOMETHING_NEW".to_owned())),
+)]
+#[tokio::test]
+async fn recognizes_failed_result_codes(code: &str, expected: ResultCode, server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200)
+            .header("Content-Type", "applucation/json")
+            .body(format!(
+                "{{\"resultCode\": \"{}\", \"trackingId\": \"AZAZA11\"}}",
+                code
+            ));
+    });
+
+    let got = make_client(&server)
+        .ping("ultra-device-id", "ultra-session-id")
+        .await
+        .unwrap_err();
+
+    assert_eq!(got.result_code(), Some(&expected))
+}
+
+#[rstest(
+    code,
+    session_expired,
+    authentication_failed,
+    rate_limited,
+    case(ResultCode::InsufficientPrivileges, true, false, false),
+    case(ResultCode::AuthenticationFailed, false, true, false),
+    case(ResultCode::ConfirmationFailed, false, true, false),
+    case(ResultCode::RequestRateLimitExceeded, false, false, true),
+    case(ResultCode::InvalidRequestData, false, false, false)
+)]
+fn classifies_api_errors(
+    code: ResultCode,
+    session_expired: bool,
+    authentication_failed: bool,
+    rate_limited: bool,
+) {
+    let got = ResponsePayload::<Nothing> {
+        result_code: code,
+        payload: None,
+        confirmations: None,
+        initial_operation: None,
+        operation_ticket: None,
+        error_message: None,
+        plain_message: None,
+    }
+    .into_result()
+    .unwrap_err();
+
+    assert_eq!(got.is_session_expired(), session_expired);
+    assert_eq!(got.is_authentication_failed(), authentication_failed);
+    assert_eq!(got.is_rate_limited(), rate_limited);
+}
+
+#[rstest(code, case(ResultCode::Ok), case(ResultCode::WaitingConfirmation))]
+fn keeps_successful_payload(code: ResultCode) {
+    let payload = ResponsePayload::<Nothing> {
+        result_code: code,
+        payload: None,
+        confirmations: None,
+        initial_operation: None,
+        operation_ticket: None,
+        error_message: None,
+        plain_message: None,
+    };
+
+    assert!(payload.into_result().is_ok())
+}
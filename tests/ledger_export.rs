@@ -0,0 +1,173 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use rstest::*;
+use rust_decimal_macros::dec;
+use serde_json::Map;
+use tinkoff_bank::{
+    write_beancount, write_ledger, Currency, LedgerOptions, MoneyAmount, Operation, OperationGroup,
+    OperationType,
+};
+
+fn dt(value: &str) -> DateTime<Utc> {
+    value.parse::<DateTime<Utc>>().unwrap()
+}
+
+#[fixture]
+fn operations() -> Vec<Operation> {
+    vec![
+        Operation {
+            id: "1234567890".to_owned(),
+            operation_type: OperationType::Credit,
+            description: "Иванов И.".to_owned(),
+            amount: MoneyAmount::new(Currency::RUB, dec!(5000)),
+            account_amount: MoneyAmount::new(Currency::RUB, dec!(5000)),
+            operation_time: dt("2021-02-01T10:00:00Z"),
+            spending_category: "Пополнения".to_owned(),
+            mcc: 0,
+            category: "Другое".to_owned(),
+            subcategory: None,
+            account: "100".to_owned(),
+            merchant: None,
+            group: OperationGroup::Income,
+            subgroup: None,
+            extra: Map::new(),
+        },
+        Operation {
+            id: "1234567891".to_owned(),
+            operation_type: OperationType::Debit,
+            description: "Кафе \"Ромашка\"".to_owned(),
+            amount: MoneyAmount::new(Currency::RUB, dec!(1234.50)),
+            account_amount: MoneyAmount::new(Currency::RUB, dec!(1234.50)),
+            operation_time: dt("2021-02-18T22:07:19Z"),
+            spending_category: "Рестораны".to_owned(),
+            mcc: 5812,
+            category: "Рестораны".to_owned(),
+            subcategory: None,
+            account: "100".to_owned(),
+            merchant: Some("Ромашка".to_owned()),
+            group: OperationGroup::Pay,
+            subgroup: None,
+            extra: Map::new(),
+        },
+        Operation {
+            id: "1234567892".to_owned(),
+            operation_type: OperationType::Debit,
+            description: "Amazon".to_owned(),
+            amount: MoneyAmount::new(Currency::USD, dec!(2)),
+            account_amount: MoneyAmount::new(Currency::RUB, dec!(145.3)),
+            operation_time: dt("2021-02-20T17:36:39Z"),
+            spending_category: "Маркетплейсы".to_owned(),
+            mcc: 5942,
+            category: "Другое".to_owned(),
+            subcategory: None,
+            account: "100".to_owned(),
+            merchant: Some("Amazon".to_owned()),
+            group: OperationGroup::Pay,
+            subgroup: None,
+            extra: Map::new(),
+        },
+    ]
+}
+
+#[fixture]
+fn options() -> LedgerOptions {
+    LedgerOptions::new("Assets:Tinkoff:Black")
+        .merchant("Amazon", "Expenses:Shopping:Books")
+        .mcc(5812, "Expenses:Food:Restaurants")
+        .spending_category("Маркетплейсы", "Expenses:Shopping")
+        .spending_category("Пополнения", "Income:Transfers")
+        .with_timezone(FixedOffset::east_opt(3 * 3600).unwrap())
+}
+
+#[rstest]
+fn writes_ledger(operations: Vec<Operation>, options: LedgerOptions) {
+    let mut buffer = vec![];
+    write_ledger(&mut buffer, &operations, &options).unwrap();
+
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        "2021-02-01 * Иванов И.
+    ; id: 1234567890
+    Income:Transfers  -5000 RUB
+    Assets:Tinkoff:Black  5000 RUB
+
+2021-02-19 * Ромашка
+    ; id: 1234567891
+    Expenses:Food:Restaurants  1234.50 RUB
+    Assets:Tinkoff:Black  -1234.50 RUB
+
+2021-02-20 * Amazon
+    ; id: 1234567892
+    Expenses:Shopping:Books  2 USD @@ 145.3 RUB
+    Assets:Tinkoff:Black  -145.3 RUB
+
+"
+    )
+}
+
+#[rstest]
+fn writes_beancount(operations: Vec<Operation>, options: LedgerOptions) {
+    let mut buffer = vec![];
+    write_beancount(&mut buffer, &operations, &options).unwrap();
+
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        r#"2021-02-01 * "Иванов И."
+  id: "1234567890"
+  Income:Transfers  -5000 RUB
+  Assets:Tinkoff:Black  5000 RUB
+
+2021-02-19 * "Ромашка" "Кафе \"Ромашка\""
+  id: "1234567891"
+  Expenses:Food:Restaurants  1234.50 RUB
+  Assets:Tinkoff:Black  -1234.50 RUB
+
+2021-02-20 * "Amazon" "Amazon"
+  id: "1234567892"
+  Expenses:Shopping:Books  2 USD @@ 145.3 RUB
+  Assets:Tinkoff:Black  -145.3 RUB
+
+"#
+    )
+}
+
+#[rstest]
+fn maps_merchant_then_mcc_then_spending_category(
+    operations: Vec<Operation>,
+    options: LedgerOptions,
+) {
+    let mut operation = operations[2].clone();
+    assert_eq!(options.account_for(&operation), "Expenses:Shopping:Books");
+
+    operation.merchant = None;
+    assert_eq!(options.account_for(&operation), "Expenses:Shopping");
+
+    let options = options.mcc(5942, "Expenses:Shopping:Online");
+    assert_eq!(options.account_for(&operation), "Expenses:Shopping:Online");
+}
+
+#[rstest]
+fn falls_back_to_default_accounts(operations: Vec<Operation>) {
+    let options = LedgerOptions::new("Assets:Tinkoff").with_default_expense("Expenses:Misc");
+
+    assert_eq!(options.account_for(&operations[0]), "Income:Unknown");
+    assert_eq!(options.account_for(&operations[1]), "Expenses:Misc");
+}
+
+#[rstest]
+fn writes_foreign_credit_with_total_price(operations: Vec<Operation>, options: LedgerOptions) {
+    let mut refund = operations[2].clone();
+    refund.operation_type = OperationType::Credit;
+
+    let mut buffer = vec![];
+    write_ledger(&mut buffer, &[refund], &options).unwrap();
+
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        "2021-02-20 * Amazon
+    ; id: 1234567892
+    Expenses:Shopping:Books  -2 USD @@ 145.3 RUB
+    Assets:Tinkoff:Black  145.3 RUB
+
+"
+    )
+}
@@ -1,5 +1,7 @@
 use httpmock::MockServer;
 use rstest::*;
+use rust_decimal_macros::dec;
+use serde_json::{json, Map, Value};
 use tinkoff_bank::{Account, Client, Currency, MoneyAmount, ResponsePayload, ResultCode};
 
 const RESPONSE: &str = "{\"payload\": [{\"externalAccountNumber\": \"100000\", \"accountGroup\": \"Дебетовые карты\", \"moneyAmount\": {\"currency\": {\"code\": 643, \"name\": \"RUB\", \"strCode\": \"643\"}, \"value\": 1111.11}, \"currency\": {\"code\": 643, \"name\": \"RUB\", \"strCode\": \"643\"}, \"name\": \"Счет Tinkoff Black BE\", \"id\": \"100\"}, {\"externalAccountNumber\": \"200000\", \"accountGroup\": \"Дебетовые карты\", \"moneyAmount\": {\"currency\": {\"code\": 840, \"name\": \"USD\", \"strCode\": \"840\"}, \"value\": 22222.2}, \"currency\": {\"code\": 840, \"name\": \"USD\", \"strCode\": \"840\"}, \"name\": \"Счет USD Tinkoff Black\", \"id\": \"200\"}, {\"externalAccountNumber\": \"300000\", \"accountGroup\": \"Накопительные счета\", \"moneyAmount\": {\"currency\": {\"code\": 643, \"name\": \"RUB\", \"strCode\": \"643\"}, \"value\": 333333}, \"currency\": {\"code\": 643, \"name\": \"RUB\", \"strCode\": \"643\"}, \"name\": \"Классный счет\", \"id\": \"300\"}], \"details\": {\"hasNext\": false}, \"resultCode\": \"OK\", \"trackingId\": \"AZAZA11\"}";
@@ -13,6 +15,10 @@ fn make_client(server: &MockServer) -> Client {
     Client::new(&server.base_url())
 }
 
+fn extra(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
 #[rstest]
 #[tokio::test]
 async fn returns_accounts(server: MockServer) {
@@ -26,7 +32,8 @@ async fn returns_accounts(server: MockServer) {
 
     let got = make_client(&server)
         .list_accounts("ultra-device-id", "ultra-session-id")
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(
         got,
@@ -38,35 +45,46 @@ async fn returns_accounts(server: MockServer) {
                     group: "Дебетовые карты".to_owned(),
                     money_amount: MoneyAmount {
                         currency: Currency::RUB,
-                        value: 1111.11
+                        value: dec!(1111.11)
                     },
                     name: "Счет Tinkoff Black BE".to_owned(),
-                    id: "100".to_owned()
+                    id: "100".to_owned(),
+                    extra: extra(json!({
+                        "currency": {"code": 643, "name": "RUB", "strCode": "643"}
+                    }))
                 },
                 Account {
                     external_number: "200000".to_owned(),
                     group: "Дебетовые карты".to_owned(),
                     money_amount: MoneyAmount {
                         currency: Currency::USD,
-                        value: 22222.2
+                        value: dec!(22222.2)
                     },
                     name: "Счет USD Tinkoff Black".to_owned(),
-                    id: "200".to_owned()
+                    id: "200".to_owned(),
+                    extra: extra(json!({
+                        "currency": {"code": 840, "name": "USD", "strCode": "840"}
+                    }))
                 },
                 Account {
                     external_number: "300000".to_owned(),
                     group: "Накопительные счета".to_owned(),
                     money_amount: MoneyAmount {
                         currency: Currency::RUB,
-                        value: 333333.0
+                        value: dec!(333333)
                     },
                     name: "Классный счет".to_owned(),
-                    id: "300".to_owned()
+                    id: "300".to_owned(),
+                    extra: extra(json!({
+                        "currency": {"code": 643, "name": "RUB", "strCode": "643"}
+                    }))
                 }
             ]),
             confirmations: None,
             initial_operation: None,
             operation_ticket: None,
+            error_message: None,
+            plain_message: None,
         }
     )
 }
@@ -86,7 +104,8 @@ async fn passes_params(server: MockServer) {
 
     make_client(&server)
         .list_accounts("ultra-device-id", "ultra-session-id")
-        .await;
+        .await
+        .unwrap();
 
     mock.assert()
 }
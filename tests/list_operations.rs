@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use httpmock::MockServer;
 use rstest::*;
+use rust_decimal_macros::dec;
+use serde_json::{json, Map, Value};
 use tinkoff_bank::{
     Client, Currency, MoneyAmount, Operation, OperationGroup, OperationType, ResponsePayload,
     ResultCode,
@@ -19,6 +21,10 @@ fn dt(value: &str) -> DateTime<Utc> {
     value.parse::<DateTime<Utc>>().unwrap()
 }
 
+fn extra(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
 #[rstest(
     response,
     expected,
@@ -28,11 +34,11 @@ fn dt(value: &str) -> DateTime<Utc> {
         description: "Яндекс.Еда".to_owned(),
         amount: MoneyAmount {
             currency: Currency::RUB,
-            value: 1234.5
+            value: dec!(1234.5)
         },
         account_amount: MoneyAmount {
             currency: Currency::RUB,
-            value: 1234.5
+            value: dec!(1234.5)
         },
         operation_time: "2021-02-18T09:07:19Z".parse::<DateTime<Utc>>().unwrap(),
         spending_category: "Рестораны".to_owned(),
@@ -43,6 +49,11 @@ fn dt(value: &str) -> DateTime<Utc> {
         merchant: Some("Яндекс.Еда".to_owned()),
         group: OperationGroup::Pay,
         subgroup: None,
+        extra: extra(json!({
+            "authMessage": "Операция утверждена.",
+            "card": "123456789",
+            "cardNumber": "553612******3456"
+        })),
     }),
     case(RESPONSE_2, Operation {
         id: "1234567891".to_owned(),
@@ -50,11 +61,11 @@ fn dt(value: &str) -> DateTime<Utc> {
         description: "Онлайм".to_owned(),
         amount: MoneyAmount {
             currency: Currency::RUB,
-            value: 100.0
+            value: dec!(100)
         },
         account_amount: MoneyAmount {
             currency: Currency::RUB,
-            value: 100.0
+            value: dec!(100)
         },
         operation_time: "2021-02-12T22:23:26Z".parse::<DateTime<Utc>>().unwrap(),
         spending_category: "Интернет".to_owned(),
@@ -65,6 +76,23 @@ fn dt(value: &str) -> DateTime<Utc> {
         merchant: None,
         group: OperationGroup::Pay,
         subgroup: Some("".to_owned()),
+        extra: extra(json!({
+            "payment": {
+                "bankAccountId": "100",
+                "paymentId": "100500",
+                "providerGroupId": "Интернет",
+                "paymentType": "Payment",
+                "feeAmount": {
+                    "currency": {"code": 643, "name": "RUB", "strCode": "643"},
+                    "value": 0.0
+                },
+                "providerId": "rostelekom-prosto",
+                "fieldsValues": {"account": "123654"},
+                "cardNumber": "553612******3456"
+            },
+            "card": "123456789",
+            "cardNumber": "553612******3456"
+        })),
     }),
     case(RESPONSE_3, Operation {
         id: "1234567892".to_owned(),
@@ -72,11 +100,11 @@ fn dt(value: &str) -> DateTime<Utc> {
         description: "Иванов И.".to_owned(),
         amount: MoneyAmount {
             currency: Currency::USD,
-            value: 2.0
+            value: dec!(2)
         },
         account_amount: MoneyAmount {
             currency: Currency::RUB,
-            value: 145.3
+            value: dec!(145.3)
         },
         operation_time: "2021-02-10T17:36:39Z".parse::<DateTime<Utc>>().unwrap(),
         spending_category: "Пополнения".to_owned(),
@@ -87,6 +115,12 @@ fn dt(value: &str) -> DateTime<Utc> {
         merchant: None,
         group: OperationGroup::Income,
         subgroup: Some("Пополнение по номеру телефона".to_owned()),
+        extra: extra(json!({
+            "message": "Перевод денежных средств",
+            "senderDetails": "Иванов И.",
+            "card": "123456789",
+            "cardNumber": "553612******3456"
+        })),
     }),
 )]
 #[tokio::test]
@@ -106,7 +140,8 @@ async fn returns_operations(response: &str, expected: Operation, server: MockSer
             dt("2009-02-13T23:31:30Z"),
             dt("2009-02-13T23:33:10Z"),
         )
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(
         got,
@@ -116,10 +151,47 @@ async fn returns_operations(response: &str, expected: Operation, server: MockSer
             confirmations: None,
             initial_operation: None,
             operation_ticket: None,
+            error_message: None,
+            plain_message: None,
         }
     )
 }
 
+#[rstest]
+#[tokio::test]
+async fn keeps_unknown_enum_values(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(200)
+            .header("Content-Type", "applucation/json")
+            .body(RESPONSE_WITH_UNKNOWN_VALUES);
+    });
+
+    let got = make_client(&server)
+        .list_operations(
+            "ultra-device-id",
+            "ultra-session-id",
+            "100",
+            dt("2009-02-13T23:31:30Z"),
+            dt("2009-02-13T23:33:10Z"),
+        )
+        .await
+        .unwrap()
+        .payload
+        .unwrap();
+
+    assert_eq!(got.len(), 2);
+    assert_eq!(
+        got[0].operation_type,
+        OperationType::Unknown("Refund".to_owned())
+    );
+    assert_eq!(got[0].operation_type.as_str(), "Refund");
+    assert_eq!(got[0].group, OperationGroup::Unknown("LOAN".to_owned()));
+    assert_eq!(got[0].group.as_str(), "LOAN");
+    assert_eq!(got[1].operation_type, OperationType::Debit);
+    assert_eq!(got[1].group, OperationGroup::Pay);
+}
+
 #[rstest]
 #[tokio::test]
 async fn passes_params(server: MockServer) {
@@ -142,7 +214,8 @@ async fn passes_params(server: MockServer) {
             dt("2009-02-13T23:31:30Z"),
             dt("2009-02-13T23:33:10Z"),
         )
-        .await;
+        .await
+        .unwrap();
 
     mock.assert()
 }
@@ -278,3 +351,49 @@ const RESPONSE_3: &str = "{
     \"resultCode\": \"OK\",
     \"trackingId\": \"AZAZA11\"
 }";
+
+const RESPONSE_WITH_UNKNOWN_VALUES: &str = "{
+    \"payload\": [
+        {
+            \"id\": \"1234567893\",
+            \"type\": \"Refund\",
+            \"description\": \"Кредит\",
+            \"amount\": {
+                \"currency\": {\"code\": 643, \"name\": \"RUB\", \"strCode\": \"643\"},
+                \"value\": 10.0
+            },
+            \"accountAmount\": {
+                \"currency\": {\"code\": 643, \"name\": \"RUB\", \"strCode\": \"643\"},
+                \"value\": 10.0
+            },
+            \"operationTime\": {\"milliseconds\": 1613639239000},
+            \"spendingCategory\": {\"id\": \"1\", \"name\": \"Кредиты\"},
+            \"mcc\": 0,
+            \"category\": {\"id\": \"1\", \"name\": \"Кредиты\"},
+            \"account\": \"100\",
+            \"group\": \"LOAN\"
+        },
+        {
+            \"id\": \"1234567894\",
+            \"type\": \"Debit\",
+            \"description\": \"Онлайм\",
+            \"amount\": {
+                \"currency\": {\"code\": 643, \"name\": \"RUB\", \"strCode\": \"643\"},
+                \"value\": 100.0
+            },
+            \"accountAmount\": {
+                \"currency\": {\"code\": 643, \"name\": \"RUB\", \"strCode\": \"643\"},
+                \"value\": 100.0
+            },
+            \"operationTime\": {\"milliseconds\": 1613168606000},
+            \"spendingCategory\": {\"id\": \"37\", \"name\": \"Интернет\"},
+            \"mcc\": 2,
+            \"category\": {\"id\": \"40\", \"name\": \"Интернет, voip/иб\"},
+            \"account\": \"100\",
+            \"group\": \"PAY\"
+        }
+    ],
+    \"details\": {\"hasNext\": false},
+    \"resultCode\": \"OK\",
+    \"trackingId\": \"AZAZA11\"
+}";
@@ -0,0 +1,206 @@
+use chrono::{DateTime, Duration, Utc};
+use httpmock::MockServer;
+use rstest::*;
+use serde_json::json;
+use tinkoff_bank::{Client, Error, RangeOptions};
+
+#[fixture]
+fn server() -> MockServer {
+    MockServer::start()
+}
+
+fn make_client(server: &MockServer) -> Client {
+    Client::new(&server.base_url())
+}
+
+fn dt(value: &str) -> DateTime<Utc> {
+    value.parse::<DateTime<Utc>>().unwrap()
+}
+
+fn response(operations: &[(&str, &str)]) -> String {
+    let payload: Vec<_> = operations
+        .iter()
+        .map(|(id, time)| {
+            json!({
+                "id": id,
+                "type": "Debit",
+                "description": "Онлайм",
+                "amount": {"currency": {"code": 643, "name": "RUB", "strCode": "643"}, "value": 100.0},
+                "accountAmount": {"currency": {"code": 643, "name": "RUB", "strCode": "643"}, "value": 100.0},
+                "operationTime": {"milliseconds": dt(time).timestamp_millis()},
+                "spendingCategory": {"name": "Интернет"},
+                "mcc": 2,
+                "category": {"name": "Интернет, voip/иб"},
+                "account": "100",
+                "group": "PAY"
+            })
+        })
+        .collect();
+
+    json!({"payload": payload, "resultCode": "OK", "trackingId": "AZAZA11"}).to_string()
+}
+
+fn mock_window<'a>(
+    server: &'a MockServer,
+    start: &str,
+    end: &str,
+    operations: &[(&str, &str)],
+) -> httpmock::Mock<'a> {
+    let body = format!(
+        "account=100&start={}&end={}",
+        dt(start).timestamp_millis(),
+        dt(end).timestamp_millis()
+    );
+    let response = response(operations);
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/operations")
+            .body(body);
+        then.status(200).body(response);
+    })
+}
+
+#[rstest]
+#[tokio::test]
+async fn fetches_interval_by_windows(server: MockServer) {
+    let first = mock_window(
+        &server,
+        "2021-01-01T00:00:00Z",
+        "2021-01-31T00:00:00Z",
+        &[("2", "2021-01-20T00:00:00Z"), ("1", "2021-01-10T00:00:00Z")],
+    );
+    let second = mock_window(
+        &server,
+        "2021-01-31T00:00:00Z",
+        "2021-03-02T00:00:00Z",
+        // the first one is on the edge of windows, so returned twice
+        &[("3", "2021-01-31T00:00:00Z"), ("4", "2021-02-15T00:00:00Z")],
+    );
+    let third = mock_window(
+        &server,
+        "2021-03-02T00:00:00Z",
+        "2021-03-10T00:00:00Z",
+        &[("5", "2021-03-05T00:00:00Z"), ("3", "2021-01-31T00:00:00Z")],
+    );
+
+    let got = make_client(&server)
+        .list_operations_range(
+            "ultra-device-id",
+            "ultra-session-id",
+            "100",
+            dt("2021-01-01T00:00:00Z"),
+            dt("2021-03-10T00:00:00Z"),
+            &RangeOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    first.assert();
+    second.assert();
+    third.assert();
+    assert_eq!(
+        got.iter().map(|op| op.id.as_str()).collect::<Vec<_>>(),
+        vec!["1", "2", "3", "4", "5"]
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn uses_configured_window(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(200).body(response(&[]));
+    });
+
+    let got = make_client(&server)
+        .list_operations_range(
+            "ultra-device-id",
+            "ultra-session-id",
+            "100",
+            dt("2021-01-01T00:00:00Z"),
+            dt("2021-01-11T00:00:00Z"),
+            &RangeOptions::default()
+                .with_window(Duration::days(1))
+                .with_concurrency(2),
+        )
+        .await
+        .unwrap();
+
+    mock.assert_hits(10);
+    assert!(got.is_empty());
+}
+
+#[rstest]
+#[tokio::test]
+async fn fails_if_any_window_fails(server: MockServer) {
+    mock_window(
+        &server,
+        "2021-01-01T00:00:00Z",
+        "2021-01-31T00:00:00Z",
+        &[("1", "2021-01-10T00:00:00Z")],
+    );
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(502).body("Bad Gateway");
+    });
+
+    let got = make_client(&server)
+        .list_operations_range(
+            "ultra-device-id",
+            "ultra-session-id",
+            "100",
+            dt("2021-01-01T00:00:00Z"),
+            dt("2021-02-10T00:00:00Z"),
+            &RangeOptions::default(),
+        )
+        .await;
+
+    assert!(got.is_err());
+}
+
+#[rstest(window, case(Duration::zero()), case(Duration::days(-1)))]
+#[tokio::test]
+async fn rejects_non_positive_window(window: Duration, server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(200).body(response(&[]));
+    });
+
+    let got = make_client(&server)
+        .list_operations_range(
+            "ultra-device-id",
+            "ultra-session-id",
+            "100",
+            dt("2021-01-01T00:00:00Z"),
+            dt("2021-01-11T00:00:00Z"),
+            &RangeOptions::default().with_window(window),
+        )
+        .await;
+
+    mock.assert_hits(0);
+    assert!(matches!(got, Err(Error::InvalidOption(_))));
+}
+
+#[rstest]
+#[tokio::test]
+async fn fetches_interval_by_single_huge_window(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(200).body(response(&[]));
+    });
+
+    let got = make_client(&server)
+        .list_operations_range(
+            "ultra-device-id",
+            "ultra-session-id",
+            "100",
+            dt("2021-01-01T00:00:00Z"),
+            dt("2021-01-11T00:00:00Z"),
+            &RangeOptions::default().with_window(Duration::days(365 * 1_000_000)),
+        )
+        .await
+        .unwrap();
+
+    mock.assert_hits(1);
+    assert!(got.is_empty());
+}
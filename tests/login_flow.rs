@@ -0,0 +1,168 @@
+use httpmock::MockServer;
+use rstest::*;
+use tinkoff_bank::{AccessLevel, Client, Error, LoginFlow, LoginStep, Prompt};
+
+const SESSION: &str = "{\"payload\": {\"sessionid\": \"ultra-session-id\", \"ttl\": 9994}, \"resultCode\": \"OK\", \"trackingId\": \"AZAZA11\"}";
+const ANONYMOUS: &str = "{\"resultCode\": \"OK\", \"payload\": {\"accessLevel\": \"ANONYMOUS\", \"userId\": \"1111\"}, \"trackingId\": \"AZAZA11\"}";
+const WAITING_SMS: &str = "{\"confirmations\": [\"SMSBYID\"], \"initialOperation\": \"auth/by/phone\", \"operationTicket\": \"ultra-operation-ticket\", \"resultCode\": \"WAITING_CONFIRMATION\", \"trackingId\": \"AZAZA11\"}";
+const WAITING_PUSH: &str = "{\"confirmations\": [\"PUSH\"], \"initialOperation\": \"auth/by/phone\", \"operationTicket\": \"ultra-operation-ticket\", \"resultCode\": \"WAITING_CONFIRMATION\", \"trackingId\": \"AZAZA11\"}";
+const CANDIDATE: &str = "{\"payload\": {\"accessLevel\": \"CANDIDATE\", \"userId\": \"1234\"}, \"resultCode\": \"OK\", \"trackingId\": \"AZAZA11\"}";
+const CLIENT: &str = "{\"payload\": {\"accessLevel\": \"CLIENT\", \"userId\": \"1234\"}, \"resultCode\": \"OK\", \"trackingId\": \"AZAZA11\"}";
+const PIN_SET: &str = "{\"payload\": {\"key\": \"key-example\"}, \"resultCode\": \"OK\", \"trackingId\": \"AZAZA11\"}";
+const AUTHENTICATION_FAILED: &str = "{\"resultCode\": \"AUTHENTICATION_FAILED\", \"errorMessage\": \"Неверный пароль\", \"trackingId\": \"AZAZA11\"}";
+
+struct TestPrompt;
+
+impl Prompt for TestPrompt {
+    fn phone(&mut self) -> String {
+        "+79991112233".to_owned()
+    }
+
+    fn sms_code(&mut self) -> String {
+        "1234".to_owned()
+    }
+
+    fn password(&mut self) -> String {
+        "ultra-password".to_owned()
+    }
+}
+
+#[fixture]
+fn server() -> MockServer {
+    MockServer::start()
+}
+
+fn make_flow(server: &MockServer) -> LoginFlow {
+    LoginFlow::new(Client::new(&server.base_url()), "ultra-device-id")
+}
+
+fn mock_until_password(server: &MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/auth/session");
+        then.status(200).body(SESSION);
+    });
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(ANONYMOUS);
+    });
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/auth/by/phone")
+            .query_param("sessionid", "ultra-session-id")
+            .body("phone=%2B79991112233");
+        then.status(200).body(WAITING_SMS);
+    });
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/confirm")
+            .query_param("sessionid", "ultra-session-id")
+            .body("initialOperationTicket=ultra-operation-ticket&initialOperation=auth%2Fby%2Fphone&confirmationData=%7B%22SMSBYID%22%3A%221234%22%7D");
+        then.status(200).body(CANDIDATE);
+    });
+}
+
+#[rstest]
+#[tokio::test]
+async fn logins_from_scratch_and_sets_pin(server: MockServer) {
+    mock_until_password(&server);
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/auth/by/password")
+            .body("password=ultra-password");
+        then.status(200).body(CLIENT);
+    });
+    let pin = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/auth/pin/set")
+            .body("pinHash=ultra-hash");
+        then.status(200).body(PIN_SET);
+    });
+
+    let got = make_flow(&server)
+        .with_pin_hash("ultra-hash")
+        .run(&mut TestPrompt)
+        .await
+        .unwrap();
+
+    pin.assert();
+    assert_eq!(got.session_id(), "ultra-session-id");
+    assert_eq!(got.access_level(), &AccessLevel::Client);
+    assert_eq!(got.user_id(), Some("1234"));
+    assert_eq!(got.credentials().pin_hash, Some("ultra-hash".to_owned()));
+}
+
+#[rstest]
+#[tokio::test]
+async fn goes_through_steps_by_access_level(server: MockServer) {
+    mock_until_password(&server);
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/auth/by/password");
+        then.status(200).body(CLIENT);
+    });
+    let mut flow = make_flow(&server);
+    let mut steps = vec![flow.step().clone()];
+
+    while flow.step() != &LoginStep::Done {
+        steps.push(flow.next(&mut TestPrompt).await.unwrap().clone());
+    }
+
+    assert_eq!(
+        steps,
+        vec![
+            LoginStep::RequestSession,
+            LoginStep::AuthByPhone,
+            LoginStep::ConfirmPhone {
+                operation_ticket: "ultra-operation-ticket".to_owned()
+            },
+            LoginStep::AuthByPassword,
+            LoginStep::Done,
+        ]
+    )
+}
+
+#[rstest]
+#[tokio::test]
+async fn keeps_step_if_it_failed(server: MockServer) {
+    mock_until_password(&server);
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/auth/by/password");
+        then.status(200).body(AUTHENTICATION_FAILED);
+    });
+    let mut flow = make_flow(&server);
+    while flow.step() != &LoginStep::AuthByPassword {
+        flow.next(&mut TestPrompt).await.unwrap();
+    }
+
+    let got = flow.next(&mut TestPrompt).await;
+
+    assert!(got.unwrap_err().is_authentication_failed());
+    assert_eq!(flow.step(), &LoginStep::AuthByPassword);
+}
+
+#[rstest]
+#[tokio::test]
+async fn fails_on_unsupported_confirmation(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/auth/session");
+        then.status(200).body(SESSION);
+    });
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(ANONYMOUS);
+    });
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/auth/by/phone");
+        then.status(200).body(WAITING_PUSH);
+    });
+
+    let got = make_flow(&server).run(&mut TestPrompt).await;
+
+    match got {
+        Err(Error::UnsupportedConfirmation(kinds)) => assert_eq!(kinds, vec!["PUSH".to_owned()]),
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("login must fail"),
+    }
+}
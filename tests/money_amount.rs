@@ -0,0 +1,68 @@
+use rstest::*;
+use rust_decimal_macros::dec;
+use std::cmp::Ordering;
+use tinkoff_bank::{Currency, Decimal, Error, MoneyAmount};
+
+fn rub(value: Decimal) -> MoneyAmount {
+    MoneyAmount::new(Currency::RUB, value)
+}
+
+#[rstest(raw, expected,
+    case("1234.56", dec!(1234.56)),
+    case("12345678.91", dec!(12345678.91)),
+    case("98765432109.99", dec!(98765432109.99)),
+    case("0.1", dec!(0.1)),
+    case("333333", dec!(333333)),
+    case("-100.05", dec!(-100.05)),
+    case("\"1234.56\"", dec!(1234.56)),
+)]
+fn parses_value_exactly(raw: &str, expected: Decimal) {
+    let json = format!(
+        "{{\"currency\": {{\"code\": 643, \"name\": \"RUB\", \"strCode\": \"643\"}}, \"value\": {}}}",
+        raw
+    );
+
+    let got: MoneyAmount = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(got, rub(expected))
+}
+
+#[test]
+fn adds_amounts_without_precision_loss() {
+    let got = rub(dec!(0.1)).checked_add(&rub(dec!(0.2))).unwrap();
+
+    assert_eq!(got, rub(dec!(0.3)))
+}
+
+#[test]
+fn subtracts_amounts() {
+    let got = rub(dec!(12345678.91))
+        .checked_sub(&rub(dec!(0.01)))
+        .unwrap();
+
+    assert_eq!(got, rub(dec!(12345678.90)))
+}
+
+#[rstest(left, right, expected,
+    case(dec!(1.5), dec!(1.50), Ordering::Equal),
+    case(dec!(1.49), dec!(1.5), Ordering::Less),
+    case(dec!(100), dec!(99.99), Ordering::Greater),
+)]
+fn compares_amounts(left: Decimal, right: Decimal, expected: Ordering) {
+    assert_eq!(rub(left).compare(&rub(right)).unwrap(), expected)
+}
+
+#[test]
+fn refuses_to_mix_currencies() {
+    let usd = MoneyAmount::new(Currency::USD, dec!(1));
+
+    assert!(matches!(
+        rub(dec!(1)).checked_add(&usd),
+        Err(Error::CurrencyMismatch {
+            left: Currency::RUB,
+            right: Currency::USD
+        })
+    ));
+    assert!(rub(dec!(1)).checked_sub(&usd).is_err());
+    assert!(rub(dec!(1)).compare(&usd).is_err());
+}
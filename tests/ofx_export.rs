@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use rstest::*;
+use rust_decimal_macros::dec;
+use serde_json::Map;
+use tinkoff_bank::{
+    write_ofx, Account, Currency, MoneyAmount, OfxOptions, Operation, OperationGroup, OperationType,
+};
+
+fn dt(value: &str) -> DateTime<Utc> {
+    value.parse::<DateTime<Utc>>().unwrap()
+}
+
+#[fixture]
+fn account() -> Account {
+    Account {
+        external_number: "5000000000000000000".to_owned(),
+        group: "Дебетовые карты".to_owned(),
+        money_amount: MoneyAmount::new(Currency::RUB, dec!(1111.11)),
+        name: "Счет Tinkoff Black".to_owned(),
+        id: "100".to_owned(),
+        extra: Map::new(),
+    }
+}
+
+#[fixture]
+fn operations() -> Vec<Operation> {
+    vec![
+        Operation {
+            id: "1234567890".to_owned(),
+            operation_type: OperationType::Credit,
+            description: "Иванов И.".to_owned(),
+            amount: MoneyAmount::new(Currency::RUB, dec!(5000)),
+            account_amount: MoneyAmount::new(Currency::RUB, dec!(5000)),
+            operation_time: dt("2021-02-01T10:00:00Z"),
+            spending_category: "Пополнения".to_owned(),
+            mcc: 0,
+            category: "Другое".to_owned(),
+            subcategory: Some("Иванов И.".to_owned()),
+            account: "100".to_owned(),
+            merchant: None,
+            group: OperationGroup::Income,
+            subgroup: None,
+            extra: Map::new(),
+        },
+        Operation {
+            id: "1234567891".to_owned(),
+            operation_type: OperationType::Debit,
+            description: "Рога & Копыта <Москва>".to_owned(),
+            amount: MoneyAmount::new(Currency::RUB, dec!(1234.50)),
+            account_amount: MoneyAmount::new(Currency::RUB, dec!(1234.50)),
+            operation_time: dt("2021-02-18T09:07:19.123Z"),
+            spending_category: "Рестораны".to_owned(),
+            mcc: 5812,
+            category: "Рестораны".to_owned(),
+            subcategory: None,
+            account: "100".to_owned(),
+            merchant: Some("Ресторан с очень длинным названием на вывеске".to_owned()),
+            group: OperationGroup::Pay,
+            subgroup: None,
+            extra: Map::new(),
+        },
+        Operation {
+            id: "1234567892".to_owned(),
+            operation_type: OperationType::Debit,
+            description: "Amazon".to_owned(),
+            amount: MoneyAmount::new(Currency::USD, dec!(2)),
+            account_amount: MoneyAmount::new(Currency::RUB, dec!(145.3)),
+            operation_time: dt("2021-02-20T17:36:39Z"),
+            spending_category: "Маркетплейсы".to_owned(),
+            mcc: 5942,
+            category: "Другое".to_owned(),
+            subcategory: None,
+            account: "100".to_owned(),
+            merchant: Some("Amazon".to_owned()),
+            group: OperationGroup::Pay,
+            subgroup: None,
+            extra: Map::new(),
+        },
+    ]
+}
+
+fn export(account: &Account, operations: &[Operation], options: &OfxOptions) -> String {
+    let mut buffer = vec![];
+    write_ofx(&mut buffer, account, operations, options).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[rstest]
+fn writes_statement(account: Account, operations: Vec<Operation>) {
+    let got = export(
+        &account,
+        &operations,
+        &OfxOptions::new(dt("2021-02-28T00:00:00Z")),
+    );
+
+    assert_eq!(got, include_str!("golden/statement.ofx"))
+}
+
+#[rstest]
+fn writes_empty_statement_of_savings_account(mut account: Account) {
+    account.group = "Накопительные счета".to_owned();
+
+    let got = export(&account, &[], &OfxOptions::new(dt("2021-02-28T00:00:00Z")));
+
+    assert_eq!(got, include_str!("golden/empty_savings.ofx"))
+}
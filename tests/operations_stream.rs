@@ -0,0 +1,208 @@
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use httpmock::MockServer;
+use rstest::*;
+use serde_json::json;
+use tinkoff_bank::{Client, Error, RangeOptions};
+
+#[fixture]
+fn server() -> MockServer {
+    MockServer::start()
+}
+
+fn make_client(server: &MockServer) -> Client {
+    Client::new(&server.base_url())
+}
+
+fn dt(value: &str) -> DateTime<Utc> {
+    value.parse::<DateTime<Utc>>().unwrap()
+}
+
+fn response(operations: &[(&str, &str)]) -> String {
+    let payload: Vec<_> = operations
+        .iter()
+        .map(|(id, time)| {
+            json!({
+                "id": id,
+                "type": "Debit",
+                "description": "Онлайм",
+                "amount": {"currency": {"code": 643, "name": "RUB", "strCode": "643"}, "value": 100.0},
+                "accountAmount": {"currency": {"code": 643, "name": "RUB", "strCode": "643"}, "value": 100.0},
+                "operationTime": {"milliseconds": dt(time).timestamp_millis()},
+                "spendingCategory": {"name": "Интернет"},
+                "mcc": 2,
+                "category": {"name": "Интернет, voip/иб"},
+                "account": "100",
+                "group": "PAY"
+            })
+        })
+        .collect();
+
+    json!({"payload": payload, "resultCode": "OK", "trackingId": "AZAZA11"}).to_string()
+}
+
+fn mock_window<'a>(
+    server: &'a MockServer,
+    start: &str,
+    end: &str,
+    operations: &[(&str, &str)],
+) -> httpmock::Mock<'a> {
+    let body = format!(
+        "account=100&start={}&end={}",
+        dt(start).timestamp_millis(),
+        dt(end).timestamp_millis()
+    );
+    let response = response(operations);
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/operations")
+            .body(body);
+        then.status(200).body(response);
+    })
+}
+
+#[rstest]
+#[tokio::test]
+async fn yields_operations_window_by_window(server: MockServer) {
+    mock_window(
+        &server,
+        "2021-01-01T00:00:00Z",
+        "2021-01-31T00:00:00Z",
+        &[("2", "2021-01-20T00:00:00Z"), ("1", "2021-01-10T00:00:00Z")],
+    );
+    mock_window(
+        &server,
+        "2021-01-31T00:00:00Z",
+        "2021-03-02T00:00:00Z",
+        &[("4", "2021-02-15T00:00:00Z"), ("3", "2021-01-31T00:00:00Z")],
+    );
+    mock_window(
+        &server,
+        "2021-03-02T00:00:00Z",
+        "2021-03-10T00:00:00Z",
+        // the last one is on the edge of windows, so returned twice
+        &[("5", "2021-03-05T00:00:00Z"), ("4", "2021-03-02T00:00:00Z")],
+    );
+
+    let got: Vec<_> = make_client(&server)
+        .operations_stream(
+            "ultra-device-id",
+            "ultra-session-id",
+            "100",
+            dt("2021-01-01T00:00:00Z"),
+            dt("2021-03-10T00:00:00Z"),
+            &RangeOptions::default(),
+        )
+        .map(|operation| operation.unwrap().id)
+        .collect()
+        .await;
+
+    assert_eq!(got, vec!["1", "2", "3", "4", "5"]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn fetches_windows_lazily(server: MockServer) {
+    let first = mock_window(
+        &server,
+        "2021-01-01T00:00:00Z",
+        "2021-01-31T00:00:00Z",
+        &[("1", "2021-01-10T00:00:00Z")],
+    );
+    let second = mock_window(
+        &server,
+        "2021-01-31T00:00:00Z",
+        "2021-03-02T00:00:00Z",
+        &[("2", "2021-02-15T00:00:00Z")],
+    );
+    let stream = make_client(&server).operations_stream(
+        "ultra-device-id",
+        "ultra-session-id",
+        "100",
+        dt("2021-01-01T00:00:00Z"),
+        dt("2021-03-02T00:00:00Z"),
+        &RangeOptions::default().with_concurrency(1),
+    );
+    futures::pin_mut!(stream);
+
+    let got = stream.next().await.unwrap().unwrap();
+
+    assert_eq!(got.id, "1");
+    first.assert_hits(1);
+    second.assert_hits(0);
+}
+
+#[rstest]
+#[tokio::test]
+async fn yields_error_of_failed_window(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(502).body("Bad Gateway");
+    });
+
+    let got: Vec<_> = make_client(&server)
+        .operations_stream(
+            "ultra-device-id",
+            "ultra-session-id",
+            "100",
+            dt("2021-01-01T00:00:00Z"),
+            dt("2021-01-10T00:00:00Z"),
+            &RangeOptions::default(),
+        )
+        .collect()
+        .await;
+
+    assert_eq!(got.len(), 1);
+    assert!(got[0].is_err());
+}
+
+#[rstest]
+#[tokio::test]
+async fn yields_error_of_non_positive_window(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(200).body(response(&[]));
+    });
+
+    let got: Vec<_> = make_client(&server)
+        .operations_stream(
+            "ultra-device-id",
+            "ultra-session-id",
+            "100",
+            dt("2021-01-01T00:00:00Z"),
+            dt("2021-01-10T00:00:00Z"),
+            &RangeOptions::default().with_window(Duration::zero()),
+        )
+        .collect()
+        .await;
+
+    mock.assert_hits(0);
+    assert_eq!(got.len(), 1);
+    assert!(matches!(got[0], Err(Error::InvalidOption(_))));
+}
+
+#[rstest]
+#[tokio::test]
+async fn fetches_interval_by_single_huge_window(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(200)
+            .body(response(&[("1", "2021-01-05T00:00:00Z")]));
+    });
+
+    let got: Vec<_> = make_client(&server)
+        .operations_stream(
+            "ultra-device-id",
+            "ultra-session-id",
+            "100",
+            dt("2021-01-01T00:00:00Z"),
+            dt("2021-01-10T00:00:00Z"),
+            &RangeOptions::default().with_window(Duration::days(365 * 1_000_000)),
+        )
+        .collect()
+        .await;
+
+    mock.assert_hits(1);
+    assert_eq!(got.len(), 1);
+    assert!(got[0].is_ok());
+}
@@ -4,6 +4,7 @@ use tinkoff_bank::{AccessLevel, Client, ResponsePayload, ResultCode, UserInfo};
 
 const ANONYMOUS: &str = "{\"resultCode\": \"OK\", \"payload\": {\"accessLevel\": \"ANONYMOUS\", \"unreadMessagesCount\": 0, \"userId\": \"1111\"}, \"trackingId\": \"AZAZA11\"}";
 const CANDIDATE: &str = "{\"resultCode\": \"OK\", \"payload\": {\"ssoId\": \"100-500-azaza-lolkek\", \"accessLevel\": \"CANDIDATE\", \"additionalAuth\": {\"needLogin\": false, \"needPassword\": true, \"needRegister\": false}, \"unreadMessagesCount\": 0, \"userId\": \"1234\"}, \"trackingId\": \"AZAZA11\"}";
+const UNKNOWN: &str = "{\"resultCode\": \"OK\", \"payload\": {\"accessLevel\": \"SUPERUSER\", \"userId\": \"1234\"}, \"trackingId\": \"AZAZA11\"}";
 const CLIENT: &str = "{\"resultCode\": \"OK\", \"payload\": {\"ssoId\": \"100-500-azaza-lolkek\", \"accessLevel\": \"CLIENT\", \"unreadMessagesCount\": 0, \"userId\": \"1234\"}, \"trackingId\": \"AZAZA11\"}";
 
 #[fixture]
@@ -37,6 +38,13 @@ fn make_client(server: &MockServer) -> Client {
             user_id: "1234".to_owned(),
         },
     ),
+    case(
+        UNKNOWN,
+        UserInfo {
+            access_level: AccessLevel::Unknown("SUPERUSER".to_owned()),
+            user_id: "1234".to_owned(),
+        },
+    ),
 )]
 #[tokio::test]
 async fn returns_user_details(resp: &str, expected: UserInfo, server: MockServer) {
@@ -49,7 +57,8 @@ async fn returns_user_details(resp: &str, expected: UserInfo, server: MockServer
 
     let got = make_client(&server)
         .ping("ultra-device-id", "ultra-session-id")
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(
         got,
@@ -59,6 +68,8 @@ async fn returns_user_details(resp: &str, expected: UserInfo, server: MockServer
             confirmations: None,
             initial_operation: None,
             operation_ticket: None,
+            error_message: None,
+            plain_message: None,
         }
     )
 }
@@ -78,7 +89,8 @@ async fn passes_params(server: MockServer) {
 
     make_client(&server)
         .ping("ultra-device-id", "ultra-session-id")
-        .await;
+        .await
+        .unwrap();
 
     mock.assert()
 }
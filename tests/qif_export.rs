@@ -0,0 +1,123 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use rstest::*;
+use rust_decimal_macros::dec;
+use serde_json::Map;
+use tinkoff_bank::{
+    write_qif, Account, Currency, Error, MoneyAmount, Operation, OperationGroup, OperationType,
+    QifOptions,
+};
+
+fn dt(value: &str) -> DateTime<Utc> {
+    value.parse::<DateTime<Utc>>().unwrap()
+}
+
+#[fixture]
+fn account() -> Account {
+    Account {
+        external_number: "5000000000000000000".to_owned(),
+        group: "Дебетовые карты".to_owned(),
+        money_amount: MoneyAmount::new(Currency::RUB, dec!(1111.11)),
+        name: "Счет Tinkoff Black".to_owned(),
+        id: "100".to_owned(),
+        extra: Map::new(),
+    }
+}
+
+#[fixture]
+fn operations() -> Vec<Operation> {
+    vec![
+        Operation {
+            id: "1234567890".to_owned(),
+            operation_type: OperationType::Credit,
+            description: "Иванов И.".to_owned(),
+            amount: MoneyAmount::new(Currency::RUB, dec!(5000)),
+            account_amount: MoneyAmount::new(Currency::RUB, dec!(5000)),
+            operation_time: dt("2021-02-01T10:00:00Z"),
+            spending_category: "Пополнения".to_owned(),
+            mcc: 0,
+            category: "Другое".to_owned(),
+            subcategory: Some("Иванов И.".to_owned()),
+            account: "100".to_owned(),
+            merchant: None,
+            group: OperationGroup::Income,
+            subgroup: None,
+            extra: Map::new(),
+        },
+        Operation {
+            id: "1234567892".to_owned(),
+            operation_type: OperationType::Debit,
+            description: "Amazon\nMarketplace".to_owned(),
+            amount: MoneyAmount::new(Currency::USD, dec!(2)),
+            account_amount: MoneyAmount::new(Currency::RUB, dec!(145.3)),
+            operation_time: dt("2021-02-20T22:36:39Z"),
+            spending_category: "Маркетплейсы".to_owned(),
+            mcc: 5942,
+            category: "Другое".to_owned(),
+            subcategory: None,
+            account: "100".to_owned(),
+            merchant: Some("Amazon".to_owned()),
+            group: OperationGroup::Pay,
+            subgroup: None,
+            extra: Map::new(),
+        },
+    ]
+}
+
+fn export(account: &Account, operations: &[Operation], options: &QifOptions) -> String {
+    let mut buffer = vec![];
+    write_qif(&mut buffer, account, operations, options).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[rstest]
+fn writes_bank_account(account: Account, operations: Vec<Operation>) {
+    let got = export(&account, &operations, &QifOptions::default());
+
+    assert_eq!(
+        got,
+        "!Type:Bank
+D02/01/2021
+T5000
+MИванов И.
+LДругое:Иванов И.
+^
+D02/20/2021
+T-145.3
+PAmazon
+MAmazon Marketplace
+LДругое
+^
+"
+    )
+}
+
+#[rstest]
+fn writes_credit_card_with_date_format(mut account: Account, operations: Vec<Operation>) {
+    account.group = "Кредитные карты".to_owned();
+    let options = QifOptions::default()
+        .with_date_format("%d.%m.%Y")
+        .with_timezone(FixedOffset::east_opt(3 * 3600).unwrap());
+
+    let got = export(&account, &operations[1..], &options);
+
+    assert_eq!(
+        got,
+        "!Type:CCard
+D21.02.2021
+T-145.3
+PAmazon
+MAmazon Marketplace
+LДругое
+^
+"
+    )
+}
+
+#[rstest]
+fn fails_on_malformed_date_format(account: Account, operations: Vec<Operation>) {
+    let options = QifOptions::default().with_date_format("%Q");
+
+    let got = write_qif(&mut vec![], &account, &operations, &options);
+
+    assert!(matches!(got, Err(Error::InvalidOption(_))));
+}
@@ -0,0 +1,163 @@
+use httpmock::MockServer;
+use rstest::*;
+use std::time::{Duration, Instant};
+use tinkoff_bank::{Client, Error, RetryPolicy};
+
+const ANONYMOUS: &str = "{\"resultCode\": \"OK\", \"payload\": {\"accessLevel\": \"ANONYMOUS\", \"userId\": \"1111\"}, \"trackingId\": \"AZAZA11\"}";
+
+#[fixture]
+fn server() -> MockServer {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(ANONYMOUS);
+    });
+    server
+}
+
+fn make_client(server: &MockServer, requests_per_second: f64, burst: u32) -> Client {
+    Client::builder()
+        .base_url(&server.base_url())
+        .rate_limit(requests_per_second, burst)
+        .build()
+        .unwrap()
+}
+
+async fn ping_times(client: &Client, times: usize) {
+    for _ in 0..times {
+        client
+            .ping("ultra-device-id", "ultra-session-id")
+            .await
+            .unwrap();
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn sends_burst_at_once(server: MockServer) {
+    let client = make_client(&server, 1.0, 3);
+    let started = Instant::now();
+
+    ping_times(&client, 3).await;
+
+    assert!(started.elapsed() < Duration::from_millis(900));
+}
+
+#[rstest]
+#[tokio::test]
+async fn limits_requests_after_burst(server: MockServer) {
+    let client = make_client(&server, 10.0, 1);
+    let started = Instant::now();
+
+    ping_times(&client, 4).await;
+
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[rstest]
+#[tokio::test]
+async fn shares_limit_between_clones(server: MockServer) {
+    let client = make_client(&server, 10.0, 1);
+    let clone = client.clone();
+    let started = Instant::now();
+
+    tokio::join!(ping_times(&client, 2), ping_times(&clone, 2));
+
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn holds_requests_for_retry_after() {
+    let server = MockServer::start();
+    let mut failing = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(503).header("Retry-After", "1");
+    });
+    let client = make_client(&server, 100.0, 10);
+    client
+        .ping("ultra-device-id", "ultra-session-id")
+        .await
+        .unwrap_err();
+    failing.delete();
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(ANONYMOUS);
+    });
+    let started = Instant::now();
+
+    ping_times(&client, 1).await;
+
+    assert!(started.elapsed() >= Duration::from_millis(900));
+}
+
+#[tokio::test]
+async fn waits_retry_after_before_retry() {
+    let server = MockServer::start();
+    let failing = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(429).header("Retry-After", "1");
+    });
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(503).body("Service Unavailable");
+    });
+    let client = Client::builder()
+        .base_url(&server.base_url())
+        .retry(
+            RetryPolicy::new(2)
+                .with_backoff(Duration::from_millis(10), Duration::from_secs(2))
+                .without_jitter(),
+        )
+        .build()
+        .unwrap();
+    let started = Instant::now();
+
+    let (got, _) = tokio::join!(client.ping("ultra-device-id", "ultra-session-id"), async {
+        while failing.hits_async().await < 1 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        failing.delete_async().await;
+    });
+
+    assert!(got.is_err());
+    assert!(started.elapsed() >= Duration::from_millis(900));
+}
+
+#[rstest(
+    requests_per_second,
+    case(0.0),
+    case(-1.0),
+    case(f64::NAN),
+    case(f64::INFINITY)
+)]
+fn rejects_invalid_rate(requests_per_second: f64) {
+    let got = Client::builder().rate_limit(requests_per_second, 1).build();
+
+    assert!(matches!(got, Err(Error::InvalidOption(_))));
+}
+
+#[tokio::test]
+async fn survives_huge_retry_after() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(503)
+            .header("Retry-After", "18446744073709551615");
+    });
+    let client = make_client(&server, 5.0, 1);
+
+    let got = client.ping("ultra-device-id", "ultra-session-id").await;
+
+    assert!(matches!(got, Err(Error::Status { .. })));
+}
+
+#[rstest]
+#[tokio::test]
+async fn holds_requests_with_tiny_rate(server: MockServer) {
+    let client = make_client(&server, 1e-20, 1);
+    ping_times(&client, 1).await;
+
+    let got = tokio::time::timeout(Duration::from_millis(100), ping_times(&client, 1)).await;
+
+    assert!(got.is_err());
+}
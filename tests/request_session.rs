@@ -25,7 +25,8 @@ async fn returns_session_details(server: MockServer) {
 
     let got = make_client(&server)
         .request_session("ultra-device-id")
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(
         got,
@@ -38,6 +39,8 @@ async fn returns_session_details(server: MockServer) {
             confirmations: None,
             initial_operation: None,
             operation_ticket: None,
+            error_message: None,
+            plain_message: None,
         }
     )
 }
@@ -0,0 +1,207 @@
+use httpmock::{Mock, MockServer};
+use rstest::*;
+use std::time::{Duration, Instant};
+use tinkoff_bank::{Client, ConfirmationData, Error, Nothing, RetryPolicy};
+
+const ANONYMOUS: &str = "{\"resultCode\": \"OK\", \"payload\": {\"accessLevel\": \"ANONYMOUS\", \"userId\": \"1111\"}, \"trackingId\": \"AZAZA11\"}";
+const RATE_LIMITED: &str =
+    "{\"resultCode\": \"REQUEST_RATE_LIMIT_EXCEEDED\", \"trackingId\": \"AZAZA11\"}";
+const INVALID_REQUEST: &str =
+    "{\"resultCode\": \"INVALID_REQUEST_DATA\", \"trackingId\": \"AZAZA11\"}";
+
+#[fixture]
+fn server() -> MockServer {
+    MockServer::start()
+}
+
+fn make_client(server: &MockServer) -> Client {
+    Client::builder()
+        .base_url(&server.base_url())
+        .retry(
+            RetryPolicy::new(3)
+                .with_backoff(Duration::from_millis(100), Duration::from_millis(100))
+                .without_jitter(),
+        )
+        .build()
+        .unwrap()
+}
+
+/// Failing mock is matched first until it is deleted after specified number of hits.
+fn mock_failures<'a>(server: &'a MockServer, status: u16, body: &str) -> Mock<'a> {
+    let failing = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(status).body(body);
+    });
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(ANONYMOUS);
+    });
+
+    failing
+}
+
+async fn stop_after(mock: &Mock<'_>, hits: usize) {
+    while mock.hits_async().await < hits {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    mock.delete_async().await;
+}
+
+#[rstest(status, body, case(502, "Bad Gateway"), case(200, RATE_LIMITED))]
+#[tokio::test]
+async fn retries_until_success(status: u16, body: &str, server: MockServer) {
+    let failing = mock_failures(&server, status, body);
+    let client = make_client(&server);
+
+    let (got, _) = tokio::join!(
+        client.ping("ultra-device-id", "ultra-session-id"),
+        stop_after(&failing, 2)
+    );
+
+    assert!(got.unwrap().payload.is_some());
+}
+
+#[rstest]
+#[tokio::test]
+async fn retries_generic_call(server: MockServer) {
+    let failing = mock_failures(&server, 502, "Bad Gateway");
+    let client = make_client(&server);
+
+    let (got, _) = tokio::join!(
+        client.call::<serde_json::Value>(
+            "/v1/ping",
+            "ultra-device-id",
+            Some("ultra-session-id"),
+            &[],
+            &[]
+        ),
+        stop_after(&failing, 2)
+    );
+
+    assert!(got.unwrap().payload.is_some());
+}
+
+#[rstest]
+#[tokio::test]
+async fn does_not_retry_confirmation_by_generic_call(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/confirm");
+        then.status(502).body("Bad Gateway");
+    });
+
+    let got = make_client(&server)
+        .call::<serde_json::Value>("/v1/confirm", "ultra-device-id", None, &[], &[])
+        .await;
+
+    mock.assert_hits(1);
+    assert!(matches!(got, Err(Error::Status { .. })));
+}
+
+#[rstest]
+#[tokio::test]
+async fn gives_up_after_max_attempts(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(503).body("Service Unavailable");
+    });
+
+    let got = make_client(&server)
+        .ping("ultra-device-id", "ultra-session-id")
+        .await;
+
+    mock.assert_hits(3);
+    assert!(matches!(got, Err(Error::Status { .. })));
+}
+
+#[rstest]
+#[tokio::test]
+async fn clamps_retry_after_to_max_backoff(server: MockServer) {
+    let failing = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(503).header("Retry-After", "86400");
+    });
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(ANONYMOUS);
+    });
+    let client = make_client(&server);
+    let started = Instant::now();
+
+    let (got, _) = tokio::join!(
+        client.ping("ultra-device-id", "ultra-session-id"),
+        stop_after(&failing, 1)
+    );
+
+    assert!(got.unwrap().payload.is_some());
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[rstest]
+#[tokio::test]
+async fn does_not_retry_permanent_failures(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/ping");
+        then.status(200).body(INVALID_REQUEST);
+    });
+
+    let got = make_client(&server)
+        .ping("ultra-device-id", "ultra-session-id")
+        .await;
+
+    mock.assert_hits(1);
+    assert!(got.is_err());
+}
+
+#[rstest]
+#[tokio::test]
+async fn does_not_retry_confirmation(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/confirm");
+        then.status(502).body("Bad Gateway");
+    });
+
+    let got = make_client(&server)
+        .confirm::<Nothing>(
+            "ultra-device-id",
+            "ultra-session-id",
+            "ultra-operation-ticket",
+            "auth/by/phone",
+            &ConfirmationData::Sms("1234".to_owned()),
+        )
+        .await;
+
+    mock.assert_hits(1);
+    assert!(matches!(got, Err(Error::Status { .. })));
+}
+
+#[test]
+fn grows_backoff_exponentially_up_to_max() {
+    let policy = RetryPolicy::new(10)
+        .with_backoff(Duration::from_millis(100), Duration::from_secs(1))
+        .without_jitter();
+
+    let got: Vec<_> = (1..=5).map(|attempt| policy.backoff(attempt)).collect();
+
+    assert_eq!(
+        got,
+        vec![
+            Duration::from_millis(100),
+            Duration::from_millis(200),
+            Duration::from_millis(400),
+            Duration::from_millis(800),
+            Duration::from_secs(1),
+        ]
+    )
+}
+
+#[test]
+fn keeps_jittered_backoff_within_bounds() {
+    let policy =
+        RetryPolicy::new(10).with_backoff(Duration::from_millis(100), Duration::from_secs(1));
+
+    for _ in 0..100 {
+        let got = policy.backoff(2);
+
+        assert!(got >= Duration::from_millis(100) && got <= Duration::from_millis(200));
+    }
+}
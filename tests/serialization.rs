@@ -0,0 +1,244 @@
+use chrono::{DateTime, TimeZone, Utc};
+use proptest::prelude::*;
+use rust_decimal_macros::dec;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Map, Value};
+use std::fmt::Debug;
+use tinkoff_bank::{
+    AccessLevel, Account, Currency, Decimal, MoneyAmount, Operation, OperationGroup, OperationType,
+    ResponsePayload, ResultCode, Session, UserInfo,
+};
+
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: &T) {
+    let json = serde_json::to_string(value).unwrap();
+    let got: T = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(&got, value, "serialized as {}", json);
+}
+
+fn decimal() -> impl Strategy<Value = Decimal> {
+    (any::<i64>(), 0u32..=6).prop_map(|(mantissa, scale)| Decimal::new(mantissa, scale))
+}
+
+fn currency() -> impl Strategy<Value = Currency> {
+    prop_oneof![
+        Just(Currency::RUB),
+        Just(Currency::USD),
+        Just(Currency::EUR),
+        Just(Currency::JPY),
+        Just(Currency::KWD),
+        Just(Currency::ALL),
+        // ISO codes have exactly three letters, so it never clashes with known currency
+        "[A-Z]{4}".prop_map(Currency::Unknown),
+    ]
+}
+
+fn money_amount() -> impl Strategy<Value = MoneyAmount> {
+    (currency(), decimal()).prop_map(|(currency, value)| MoneyAmount::new(currency, value))
+}
+
+fn operation_type() -> impl Strategy<Value = OperationType> {
+    prop_oneof![
+        Just(OperationType::Credit),
+        Just(OperationType::Debit),
+        "[a-z]{3,10}".prop_map(OperationType::Unknown),
+    ]
+}
+
+fn operation_group() -> impl Strategy<Value = OperationGroup> {
+    prop_oneof![
+        Just(OperationGroup::Pay),
+        Just(OperationGroup::Income),
+        Just(OperationGroup::Transfer),
+        Just(OperationGroup::Cash),
+        Just(OperationGroup::Correction),
+        Just(OperationGroup::Charge),
+        Just(OperationGroup::Internal),
+        "[a-z]{3,10}".prop_map(OperationGroup::Unknown),
+    ]
+}
+
+fn access_level() -> impl Strategy<Value = AccessLevel> {
+    prop_oneof![
+        Just(AccessLevel::Anonymous),
+        Just(AccessLevel::Candidate),
+        Just(AccessLevel::Client),
+        "[a-z]{3,10}".prop_map(AccessLevel::Unknown),
+    ]
+}
+
+fn operation_time() -> impl Strategy<Value = DateTime<Utc>> {
+    (0i64..4_102_444_800_000).prop_map(|ms| Utc.timestamp_millis_opt(ms).unwrap())
+}
+
+fn extra() -> impl Strategy<Value = Map<String, Value>> {
+    // prefixed keys never clash with modeled fields
+    proptest::collection::btree_map("x[a-zA-Z]{1,8}", "\\PC*", 0..3).prop_map(|fields| {
+        fields
+            .into_iter()
+            .map(|(key, value)| (key, Value::from(value)))
+            .collect()
+    })
+}
+
+fn operation() -> impl Strategy<Value = Operation> {
+    (
+        (
+            "\\PC*",
+            operation_type(),
+            "\\PC*",
+            money_amount(),
+            money_amount(),
+            operation_time(),
+            "\\PC*",
+        ),
+        (
+            any::<u16>(),
+            "\\PC*",
+            proptest::option::of("\\PC*"),
+            "\\PC*",
+            proptest::option::of("\\PC*"),
+            operation_group(),
+            proptest::option::of("\\PC*"),
+            extra(),
+        ),
+    )
+        .prop_map(
+            |(
+                (
+                    id,
+                    operation_type,
+                    description,
+                    amount,
+                    account_amount,
+                    operation_time,
+                    spending_category,
+                ),
+                (mcc, category, subcategory, account, merchant, group, subgroup, extra),
+            )| Operation {
+                id,
+                operation_type,
+                description,
+                amount,
+                account_amount,
+                operation_time,
+                spending_category,
+                mcc,
+                category,
+                subcategory,
+                account,
+                merchant,
+                group,
+                subgroup,
+                extra,
+            },
+        )
+}
+
+fn account() -> impl Strategy<Value = Account> {
+    ("\\PC*", "\\PC*", money_amount(), "\\PC*", "\\PC*", extra()).prop_map(
+        |(external_number, group, money_amount, name, id, extra)| Account {
+            external_number,
+            group,
+            money_amount,
+            name,
+            id,
+            extra,
+        },
+    )
+}
+
+proptest! {
+    #[test]
+    fn money_amount_round_trips(value in money_amount()) {
+        round_trip(&value)
+    }
+
+    #[test]
+    fn operation_round_trips(value in operation()) {
+        round_trip(&value)
+    }
+
+    #[test]
+    fn account_round_trips(value in account()) {
+        round_trip(&value)
+    }
+
+    #[test]
+    fn user_info_round_trips(access_level in access_level(), user_id in "\\PC*") {
+        round_trip(&UserInfo { access_level, user_id })
+    }
+
+    #[test]
+    fn session_round_trips(id in "\\PC*", ttl in any::<u32>()) {
+        round_trip(&Session { id, ttl })
+    }
+
+    #[test]
+    fn response_payload_round_trips(
+        payload in proptest::option::of(proptest::collection::vec(operation(), 0..3)),
+        error_message in proptest::option::of("\\PC*"),
+    ) {
+        round_trip(&ResponsePayload {
+            result_code: ResultCode::Ok,
+            payload,
+            confirmations: None,
+            initial_operation: None,
+            operation_ticket: None,
+            error_message,
+            plain_message: None,
+        })
+    }
+}
+
+#[test]
+fn serializes_operation_in_api_shape() {
+    let operation = Operation {
+        id: "1234567890".to_owned(),
+        operation_type: OperationType::Credit,
+        description: "Яндекс.Еда".to_owned(),
+        amount: MoneyAmount::new(Currency::RUB, dec!(1234.50)),
+        account_amount: MoneyAmount::new(Currency::Unknown("XYZ".to_owned()), dec!(1)),
+        operation_time: "2021-02-18T09:07:19Z".parse::<DateTime<Utc>>().unwrap(),
+        spending_category: "Рестораны".to_owned(),
+        mcc: 5812,
+        category: "Рестораны".to_owned(),
+        subcategory: None,
+        account: "100".to_owned(),
+        merchant: Some("Яндекс.Еда".to_owned()),
+        group: OperationGroup::Pay,
+        subgroup: None,
+        extra: json!({"cardNumber": "553612******3456", "debitingTime": {"milliseconds": 1613639240000i64}})
+            .as_object()
+            .unwrap()
+            .clone(),
+    };
+
+    let got = serde_json::to_value(&operation).unwrap();
+
+    assert_eq!(
+        got,
+        json!({
+            "id": "1234567890",
+            "type": "Credit",
+            "description": "Яндекс.Еда",
+            "amount": {
+                "currency": {"code": 643, "name": "RUB", "strCode": "643"},
+                "value": "1234.50"
+            },
+            "accountAmount": {
+                "currency": {"name": "XYZ"},
+                "value": "1"
+            },
+            "operationTime": {"milliseconds": 1613639239000i64},
+            "spendingCategory": {"name": "Рестораны"},
+            "mcc": 5812,
+            "category": {"name": "Рестораны"},
+            "account": "100",
+            "merchant": {"name": "Яндекс.Еда"},
+            "group": "PAY",
+            "cardNumber": "553612******3456",
+            "debitingTime": {"milliseconds": 1613639240000i64}
+        })
+    )
+}
@@ -0,0 +1,140 @@
+use httpmock::MockServer;
+use rstest::*;
+use std::fs;
+use std::path::PathBuf;
+use tinkoff_bank::{
+    AuthenticatedClient, Client, Credentials, Error, FileStore, MemoryStore, SessionStore,
+};
+use uuid::Uuid;
+
+const SESSION: &str = "{\"payload\": {\"sessionid\": \"ultra-new-session-id\", \"ttl\": 9994}, \"resultCode\": \"OK\", \"trackingId\": \"AZAZA11\"}";
+const CLIENT: &str = "{\"resultCode\": \"OK\", \"payload\": {\"accessLevel\": \"CLIENT\", \"userId\": \"1234\"}, \"trackingId\": \"AZAZA11\"}";
+
+#[fixture]
+fn server() -> MockServer {
+    MockServer::start()
+}
+
+#[fixture]
+fn credentials() -> Credentials {
+    Credentials {
+        device_id: "ultra-device-id".to_owned(),
+        session_id: Some("ultra-session-id".to_owned()),
+        pin_hash: Some("ultra-hash".to_owned()),
+        user_id: Some("1234".to_owned()),
+    }
+}
+
+fn tmp_path() -> PathBuf {
+    std::env::temp_dir().join(format!("tinkoff-bank-{}.bin", Uuid::new_v4()))
+}
+
+#[test]
+fn memory_store_is_empty_by_default() {
+    assert_eq!(MemoryStore::new().load().unwrap(), None)
+}
+
+#[rstest]
+fn memory_store_returns_saved_credentials(credentials: Credentials) {
+    let store = MemoryStore::new();
+
+    store.save(&credentials).unwrap();
+
+    assert_eq!(store.load().unwrap(), Some(credentials))
+}
+
+#[test]
+fn file_store_is_empty_if_file_is_missing() {
+    let store = FileStore::new(tmp_path(), &FileStore::generate_key());
+
+    assert_eq!(store.load().unwrap(), None)
+}
+
+#[rstest]
+fn file_store_returns_saved_credentials(credentials: Credentials) {
+    let path = tmp_path();
+    let key = FileStore::generate_key();
+    FileStore::new(&path, &key).save(&credentials).unwrap();
+
+    let got = FileStore::new(&path, &key).load().unwrap();
+
+    fs::remove_file(&path).unwrap();
+    assert_eq!(got, Some(credentials))
+}
+
+#[rstest]
+fn file_store_encrypts_credentials(credentials: Credentials) {
+    let path = tmp_path();
+    FileStore::new(&path, &FileStore::generate_key())
+        .save(&credentials)
+        .unwrap();
+
+    let content = fs::read(&path).unwrap();
+
+    fs::remove_file(&path).unwrap();
+    let content = String::from_utf8_lossy(&content);
+    assert!(!content.contains("ultra-hash"));
+    assert!(!content.contains("ultra-device-id"));
+}
+
+#[rstest]
+fn file_store_fails_with_wrong_key(credentials: Credentials) {
+    let path = tmp_path();
+    FileStore::new(&path, &FileStore::generate_key())
+        .save(&credentials)
+        .unwrap();
+
+    let got = FileStore::new(&path, &FileStore::generate_key()).load();
+
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(got, Err(Error::CorruptedStore)))
+}
+
+#[rstest]
+#[tokio::test]
+async fn resumes_session_from_store(credentials: Credentials, server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/auth/session");
+        then.status(200).body(SESSION);
+    });
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/auth/by/pin")
+            .query_param("deviceId", "ultra-device-id")
+            .query_param("sessionid", "ultra-new-session-id")
+            .body("pinHash=ultra-hash&oldSessionId=ultra-session-id&auth_type=pin");
+        then.status(200).body(CLIENT);
+    });
+    let store = MemoryStore::new();
+    store.save(&credentials).unwrap();
+
+    let got = AuthenticatedClient::resume(Client::new(&server.base_url()), &store)
+        .await
+        .unwrap()
+        .unwrap();
+
+    mock.assert();
+    assert_eq!(got.session_id(), "ultra-new-session-id");
+    assert_eq!(
+        store.load().unwrap(),
+        Some(Credentials {
+            session_id: Some("ultra-new-session-id".to_owned()),
+            ..credentials
+        })
+    );
+}
+
+#[rstest(stored, case(None), case(Some(Credentials::new("ultra-device-id"))))]
+#[tokio::test]
+async fn does_not_resume_without_pin_hash(stored: Option<Credentials>, server: MockServer) {
+    let store = MemoryStore::new();
+    if let Some(credentials) = stored {
+        store.save(&credentials).unwrap();
+    }
+
+    let got = AuthenticatedClient::resume(Client::new(&server.base_url()), &store)
+        .await
+        .unwrap();
+
+    assert!(got.is_none())
+}
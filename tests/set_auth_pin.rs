@@ -25,7 +25,8 @@ async fn returns_nothing(server: MockServer) {
 
     let got = make_client(&server)
         .set_auth_pin("ultra-device-id", "ultra-session-id", "ultra-hash")
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(
         got,
@@ -35,6 +36,8 @@ async fn returns_nothing(server: MockServer) {
             confirmations: None,
             initial_operation: None,
             operation_ticket: None,
+            error_message: None,
+            plain_message: None,
         }
     )
 }
@@ -55,7 +58,8 @@ async fn passes_params(server: MockServer) {
 
     make_client(&server)
         .set_auth_pin("ultra-device-id", "ultra-session-id", "ultra-hash")
-        .await;
+        .await
+        .unwrap();
 
     mock.assert()
 }
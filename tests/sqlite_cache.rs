@@ -0,0 +1,167 @@
+#![cfg(feature = "sqlite")]
+
+use chrono::{DateTime, Utc};
+use rstest::*;
+use rust_decimal_macros::dec;
+use serde_json::{json, Map, Value};
+use std::fs;
+use tinkoff_bank::{
+    Account, Currency, Error, MoneyAmount, Operation, OperationGroup, OperationQuery,
+    OperationType, SqliteCache,
+};
+use uuid::Uuid;
+
+fn dt(value: &str) -> DateTime<Utc> {
+    value.parse::<DateTime<Utc>>().unwrap()
+}
+
+fn extra(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+}
+
+fn operation(id: &str, account: &str, time: &str, category: &str, mcc: u16) -> Operation {
+    Operation {
+        id: id.to_owned(),
+        operation_type: OperationType::Debit,
+        description: "Яндекс.Еда".to_owned(),
+        amount: MoneyAmount::new(Currency::RUB, dec!(1234.50)),
+        account_amount: MoneyAmount::new(Currency::RUB, dec!(1234.50)),
+        operation_time: dt(time),
+        spending_category: category.to_owned(),
+        mcc,
+        category: category.to_owned(),
+        subcategory: None,
+        account: account.to_owned(),
+        merchant: Some("Яндекс.Еда".to_owned()),
+        group: OperationGroup::Pay,
+        subgroup: None,
+        extra: extra(json!({"cardNumber": "553612******3456"})),
+    }
+}
+
+#[fixture]
+fn cache() -> SqliteCache {
+    let cache = SqliteCache::open_in_memory().unwrap();
+    cache
+        .upsert_operations(&[
+            operation("3", "100", "2021-02-18T09:07:19Z", "Рестораны", 5812),
+            operation("1", "100", "2021-01-10T00:00:00Z", "Интернет", 4814),
+            operation("2", "200", "2021-02-01T00:00:00Z", "Рестораны", 5812),
+            operation("4", "100", "2021-03-01T00:00:00Z", "Супермаркеты", 5411),
+        ])
+        .unwrap();
+    cache
+}
+
+fn ids(operations: &[Operation]) -> Vec<&str> {
+    operations.iter().map(|op| op.id.as_str()).collect()
+}
+
+#[rstest(
+    query,
+    expected,
+    case(OperationQuery::new(), vec!["1", "2", "3", "4"]),
+    case(OperationQuery::new().account("100"), vec!["1", "3", "4"]),
+    case(
+        OperationQuery::new().between(dt("2021-02-01T00:00:00Z"), dt("2021-03-01T00:00:00Z")),
+        vec!["2", "3"]
+    ),
+    case(OperationQuery::new().category("Рестораны"), vec!["2", "3"]),
+    case(OperationQuery::new().mcc(5411), vec!["4"]),
+    case(OperationQuery::new().account("100").mcc(5812), vec!["3"]),
+)]
+fn queries_operations(query: OperationQuery, expected: Vec<&str>, cache: SqliteCache) {
+    let got = cache.operations(&query).unwrap();
+
+    assert_eq!(ids(&got), expected);
+}
+
+#[rstest]
+fn keeps_operations_without_loss(cache: SqliteCache) {
+    let got = cache.operations(&OperationQuery::new().mcc(4814)).unwrap();
+
+    assert_eq!(
+        got,
+        vec![operation(
+            "1",
+            "100",
+            "2021-01-10T00:00:00Z",
+            "Интернет",
+            4814
+        )]
+    );
+}
+
+#[rstest]
+fn replaces_operation_with_the_same_id(cache: SqliteCache) {
+    let mut changed = operation("1", "100", "2021-01-10T00:00:00Z", "Интернет", 4814);
+    changed.description = "Онлайм".to_owned();
+
+    cache.upsert_operations(&[changed.clone()]).unwrap();
+
+    let got = cache.operations(&OperationQuery::new()).unwrap();
+    assert_eq!(got.len(), 4);
+    assert_eq!(got[0], changed);
+}
+
+#[test]
+fn upserts_accounts() {
+    let cache = SqliteCache::open_in_memory().unwrap();
+    let mut account = Account {
+        external_number: "100000".to_owned(),
+        group: "Дебетовые карты".to_owned(),
+        money_amount: MoneyAmount::new(Currency::RUB, dec!(1111.11)),
+        name: "Счет Tinkoff Black BE".to_owned(),
+        id: "100".to_owned(),
+        extra: Map::new(),
+    };
+    cache.upsert_accounts(&[account.clone()]).unwrap();
+    account.money_amount = MoneyAmount::new(Currency::RUB, dec!(2222.22));
+
+    cache.upsert_accounts(&[account.clone()]).unwrap();
+
+    assert_eq!(cache.accounts().unwrap(), vec![account]);
+}
+
+#[test]
+fn migrates_database_once() {
+    let path = std::env::temp_dir().join(format!("tinkoff-bank-{}.sqlite", Uuid::new_v4()));
+    SqliteCache::open(&path)
+        .unwrap()
+        .upsert_operations(&[operation(
+            "1",
+            "100",
+            "2021-01-10T00:00:00Z",
+            "Интернет",
+            4814,
+        )])
+        .unwrap();
+
+    let cache = SqliteCache::open(&path).unwrap();
+
+    let version = cache.schema_version().unwrap();
+    let got = cache.operations(&OperationQuery::new()).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(version, 1);
+    assert_eq!(ids(&got), vec!["1"]);
+}
+
+#[test]
+fn refuses_database_of_newer_version() {
+    let path = std::env::temp_dir().join(format!("tinkoff-bank-{}.sqlite", Uuid::new_v4()));
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .pragma_update(None, "user_version", 99)
+        .unwrap();
+
+    let got = SqliteCache::open(&path);
+
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        got,
+        Err(Error::UnsupportedSchema {
+            version: 99,
+            supported: 1
+        })
+    ));
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use httpmock::MockServer;
+use rstest::*;
+use serde_json::json;
+use tinkoff_bank::{Client, MemorySyncStateStore, SyncStateStore, Syncer};
+
+#[fixture]
+fn server() -> MockServer {
+    MockServer::start()
+}
+
+fn hours_ago(hours: i64) -> DateTime<Utc> {
+    let now = Utc::now().timestamp_millis();
+    Utc.timestamp_millis_opt(now - hours * 3_600_000).unwrap()
+}
+
+fn response(operations: &[(&str, &str, DateTime<Utc>)]) -> String {
+    let payload: Vec<_> = operations
+        .iter()
+        .map(|(id, description, time)| {
+            json!({
+                "id": id,
+                "type": "Debit",
+                "description": description,
+                "amount": {"currency": {"code": 643, "name": "RUB", "strCode": "643"}, "value": 100.0},
+                "accountAmount": {"currency": {"code": 643, "name": "RUB", "strCode": "643"}, "value": 100.0},
+                "operationTime": {"milliseconds": time.timestamp_millis()},
+                "spendingCategory": {"name": "Интернет"},
+                "mcc": 2,
+                "category": {"name": "Интернет, voip/иб"},
+                "account": "100",
+                "group": "PAY"
+            })
+        })
+        .collect();
+
+    json!({"payload": payload, "resultCode": "OK", "trackingId": "AZAZA11"}).to_string()
+}
+
+fn make_syncer(server: &MockServer) -> Syncer {
+    Syncer::new(
+        Client::new(&server.base_url()),
+        "ultra-device-id",
+        "ultra-session-id",
+    )
+    .with_initial_start(hours_ago(48))
+    .with_overlap(Duration::days(1))
+}
+
+fn ids(operations: &[tinkoff_bank::Operation]) -> Vec<&str> {
+    operations.iter().map(|op| op.id.as_str()).collect()
+}
+
+#[rstest]
+#[tokio::test]
+async fn reports_all_operations_on_first_sync(server: MockServer) {
+    let first = hours_ago(30);
+    let second = hours_ago(12);
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(200).body(response(&[
+            ("1", "Онлайм", first),
+            ("2", "Яндекс.Еда", second),
+        ]));
+    });
+    let store = MemorySyncStateStore::new();
+
+    let got = make_syncer(&server).sync("100", &store).await.unwrap();
+
+    assert_eq!(ids(&got.added), vec!["1", "2"]);
+    assert!(got.changed.is_empty());
+    assert!(got.disappeared.is_empty());
+    let state = store.load("100").unwrap().unwrap();
+    assert_eq!(state.high_water_mark, Some(second));
+    assert_eq!(state.recent.keys().collect::<Vec<_>>(), vec!["1", "2"]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn reports_difference_with_previous_sync(server: MockServer) {
+    let first = hours_ago(30);
+    let second = hours_ago(12);
+    let mut initial = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(200).body(response(&[
+            ("1", "Онлайм", first),
+            ("2", "Яндекс.Еда", second),
+        ]));
+    });
+    let store = MemorySyncStateStore::new();
+    let syncer = make_syncer(&server);
+    syncer.sync("100", &store).await.unwrap();
+    initial.delete();
+    let next = server.mock(|when, then| {
+        when.method(httpmock::Method::POST)
+            .path("/v1/operations")
+            // starts with overlap before the latest synced operation
+            .body_contains(format!(
+                "start={}",
+                (second - Duration::days(1)).timestamp_millis()
+            ));
+        then.status(200).body(response(&[
+            ("2", "Яндекс.Еда, возврат", second),
+            ("3", "Кофе", hours_ago(6)),
+        ]));
+    });
+
+    let got = syncer.sync("100", &store).await.unwrap();
+
+    next.assert();
+    assert_eq!(ids(&got.added), vec!["3"]);
+    assert_eq!(ids(&got.changed), vec!["2"]);
+    assert_eq!(got.disappeared, vec!["1".to_owned()]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn reports_nothing_if_nothing_changed(server: MockServer) {
+    server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(200)
+            .body(response(&[("1", "Онлайм", hours_ago(30))]));
+    });
+    let store = MemorySyncStateStore::new();
+    let syncer = make_syncer(&server);
+    syncer.sync("100", &store).await.unwrap();
+
+    let got = syncer.sync("100", &store).await.unwrap();
+
+    assert!(got.added.is_empty());
+    assert!(got.changed.is_empty());
+    assert!(got.disappeared.is_empty());
+}
+
+#[rstest]
+#[tokio::test]
+async fn syncs_with_huge_overlap(server: MockServer) {
+    let mock = server.mock(|when, then| {
+        when.method(httpmock::Method::POST).path("/v1/operations");
+        then.status(200)
+            .body(response(&[("1", "Онлайм", hours_ago(30))]));
+    });
+    let store = MemorySyncStateStore::new();
+    let syncer = make_syncer(&server).with_overlap(Duration::days(365 * 1_000_000));
+    syncer.sync("100", &store).await.unwrap();
+
+    let got = syncer.sync("100", &store).await.unwrap();
+
+    mock.assert_hits(2);
+    assert!(got.added.is_empty());
+    assert!(got.disappeared.is_empty());
+    let state = store.load("100").unwrap().unwrap();
+    assert_eq!(state.recent.keys().collect::<Vec<_>>(), vec!["1"]);
+}